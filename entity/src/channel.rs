@@ -11,6 +11,8 @@ pub struct Model {
     pub guild_id: i64,
     pub last_message_id: Option<i64>,
     pub backfill_done: bool,
+    pub parent_id: Option<i64>,
+    pub nsfw: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
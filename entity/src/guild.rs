@@ -15,6 +15,8 @@ pub struct Model {
 pub enum Relation {
     #[sea_orm(has_many = "super::channel::Entity")]
     Channel,
+    #[sea_orm(has_many = "super::game::Entity")]
+    Game,
     #[sea_orm(
         belongs_to = "super::user::Entity",
         from = "Column::OwnerId",
@@ -23,6 +25,20 @@ pub enum Relation {
         on_delete = "NoAction"
     )]
     User,
+    #[sea_orm(has_one = "super::guild_config::Entity")]
+    GuildConfig,
+    #[sea_orm(has_many = "super::guild_config_channel::Entity")]
+    GuildConfigChannel,
+    #[sea_orm(has_many = "super::guild_member::Entity")]
+    GuildMember,
+    #[sea_orm(has_many = "super::guild_scrape_channel::Entity")]
+    GuildScrapeChannel,
+    #[sea_orm(has_one = "super::quote_filter::Entity")]
+    QuoteFilter,
+    #[sea_orm(has_many = "super::quote_filter_pattern::Entity")]
+    QuoteFilterPattern,
+    #[sea_orm(has_many = "super::user_consent::Entity")]
+    UserConsent,
     #[sea_orm(has_many = "super::user_emoji::Entity")]
     UserEmoji,
 }
@@ -33,6 +49,54 @@ impl Related<super::channel::Entity> for Entity {
     }
 }
 
+impl Related<super::game::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Game.def()
+    }
+}
+
+impl Related<super::guild_config::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::GuildConfig.def()
+    }
+}
+
+impl Related<super::guild_config_channel::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::GuildConfigChannel.def()
+    }
+}
+
+impl Related<super::guild_member::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::GuildMember.def()
+    }
+}
+
+impl Related<super::guild_scrape_channel::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::GuildScrapeChannel.def()
+    }
+}
+
+impl Related<super::quote_filter::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::QuoteFilter.def()
+    }
+}
+
+impl Related<super::quote_filter_pattern::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::QuoteFilterPattern.def()
+    }
+}
+
+impl Related<super::user_consent::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserConsent.def()
+    }
+}
+
 impl Related<super::user_emoji::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::UserEmoji.def()
@@ -12,6 +12,8 @@ pub struct Model {
     #[sea_orm(column_type = "Text")]
     pub content: String,
     pub timestamp: DateTimeWithTimeZone,
+    pub edited_at: Option<DateTimeWithTimeZone>,
+    pub deleted_at: Option<DateTimeWithTimeZone>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -24,6 +26,8 @@ pub enum Relation {
         on_delete = "NoAction"
     )]
     Channel,
+    #[sea_orm(has_many = "super::game_question::Entity")]
+    GameQuestion,
     #[sea_orm(
         belongs_to = "super::user::Entity",
         from = "Column::AuthorId",
@@ -40,6 +44,12 @@ impl Related<super::channel::Entity> for Entity {
     }
 }
 
+impl Related<super::game_question::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::GameQuestion.def()
+    }
+}
+
 impl Related<super::user::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::User.def()
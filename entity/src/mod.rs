@@ -3,7 +3,17 @@
 pub mod prelude;
 
 pub mod channel;
+pub mod game;
+pub mod game_answer;
+pub mod game_question;
 pub mod guild;
+pub mod guild_config;
+pub mod guild_config_channel;
+pub mod guild_member;
+pub mod guild_scrape_channel;
 pub mod message;
+pub mod quote_filter;
+pub mod quote_filter_pattern;
 pub mod user;
+pub mod user_consent;
 pub mod user_emoji;
@@ -1,7 +1,17 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.12.15
 
 pub use super::channel::Entity as Channel;
+pub use super::game::Entity as Game;
+pub use super::game_answer::Entity as GameAnswer;
+pub use super::game_question::Entity as GameQuestion;
 pub use super::guild::Entity as Guild;
+pub use super::guild_config::Entity as GuildConfig;
+pub use super::guild_config_channel::Entity as GuildConfigChannel;
+pub use super::guild_member::Entity as GuildMember;
+pub use super::guild_scrape_channel::Entity as GuildScrapeChannel;
 pub use super::message::Entity as Message;
+pub use super::quote_filter::Entity as QuoteFilter;
+pub use super::quote_filter_pattern::Entity as QuoteFilterPattern;
 pub use super::user::Entity as User;
+pub use super::user_consent::Entity as UserConsent;
 pub use super::user_emoji::Entity as UserEmoji;
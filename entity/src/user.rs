@@ -15,20 +15,60 @@ pub struct Model {
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::game::Entity")]
+    Game,
+    #[sea_orm(has_many = "super::game_answer::Entity")]
+    GameAnswer,
+    #[sea_orm(has_many = "super::game_question::Entity")]
+    GameQuestion,
     #[sea_orm(has_many = "super::guild::Entity")]
     Guild,
+    #[sea_orm(has_many = "super::guild_member::Entity")]
+    GuildMember,
     #[sea_orm(has_many = "super::message::Entity")]
     Message,
+    #[sea_orm(has_many = "super::user_consent::Entity")]
+    UserConsent,
     #[sea_orm(has_many = "super::user_emoji::Entity")]
     UserEmoji,
 }
 
+impl Related<super::game::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Game.def()
+    }
+}
+
+impl Related<super::game_answer::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::GameAnswer.def()
+    }
+}
+
+impl Related<super::game_question::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::GameQuestion.def()
+    }
+}
+
+impl Related<super::guild_member::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::GuildMember.def()
+    }
+}
+
 impl Related<super::message::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Message.def()
     }
 }
 
+impl Related<super::user_consent::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserConsent.def()
+    }
+}
+
 impl Related<super::user_emoji::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::UserEmoji.def()
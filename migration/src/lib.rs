@@ -8,6 +8,23 @@ mod m20240205_000003_create_channel_table;
 mod m20240205_000004_create_message_table;
 mod m20240407_000001_create_user_emoji_table;
 mod m20250411_000001_make_msg_author_nilable;
+mod m20261018_000001_create_game_table;
+mod m20261018_000002_create_game_question_table;
+mod m20261018_000003_create_game_answer_table;
+mod m20261018_000004_add_game_answer_points;
+mod m20261018_000005_add_message_edited_and_deleted_at;
+mod m20261018_000006_create_user_consent_table;
+mod m20261018_000007_add_channel_parent_id;
+mod m20261018_000008_create_guild_member_table;
+mod m20261018_000009_add_user_emoji_unique_index;
+mod m20261018_000010_add_game_question_kind;
+mod m20261018_000011_create_quote_filter_table;
+mod m20261018_000012_create_quote_filter_pattern_table;
+mod m20261018_000013_add_message_content_index;
+mod m20261018_000014_create_guild_config_table;
+mod m20261018_000015_create_guild_config_channel_table;
+mod m20261018_000016_add_channel_nsfw;
+mod m20261018_000017_create_guild_scrape_channel_table;
 
 pub struct Migrator;
 
@@ -21,6 +38,23 @@ impl MigratorTrait for Migrator {
             Box::new(m20240205_000004_create_message_table::Migration),
             Box::new(m20240407_000001_create_user_emoji_table::Migration),
             Box::new(m20250411_000001_make_msg_author_nilable::Migration),
+            Box::new(m20261018_000001_create_game_table::Migration),
+            Box::new(m20261018_000002_create_game_question_table::Migration),
+            Box::new(m20261018_000003_create_game_answer_table::Migration),
+            Box::new(m20261018_000004_add_game_answer_points::Migration),
+            Box::new(m20261018_000005_add_message_edited_and_deleted_at::Migration),
+            Box::new(m20261018_000006_create_user_consent_table::Migration),
+            Box::new(m20261018_000007_add_channel_parent_id::Migration),
+            Box::new(m20261018_000008_create_guild_member_table::Migration),
+            Box::new(m20261018_000009_add_user_emoji_unique_index::Migration),
+            Box::new(m20261018_000010_add_game_question_kind::Migration),
+            Box::new(m20261018_000011_create_quote_filter_table::Migration),
+            Box::new(m20261018_000012_create_quote_filter_pattern_table::Migration),
+            Box::new(m20261018_000013_add_message_content_index::Migration),
+            Box::new(m20261018_000014_create_guild_config_table::Migration),
+            Box::new(m20261018_000015_create_guild_config_channel_table::Migration),
+            Box::new(m20261018_000016_add_channel_nsfw::Migration),
+            Box::new(m20261018_000017_create_guild_scrape_channel_table::Migration),
         ]
     }
 }
@@ -53,4 +53,6 @@ pub enum Channel {
     GuildId,
     LastMessageId,
     BackfillDone,
+    ParentId,
+    Nsfw,
 }
@@ -58,4 +58,6 @@ pub enum Message {
     ChannelId,
     Content,
     Timestamp,
+    EditedAt,
+    DeletedAt,
 }
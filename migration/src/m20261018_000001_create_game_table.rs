@@ -0,0 +1,64 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20240205_000001_create_user_table::User;
+use crate::m20240205_000002_create_guild_table::Guild;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Game::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Game::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Game::GuildId).big_unsigned().not_null())
+                    .col(ColumnDef::new(Game::ChannelId).big_unsigned().not_null())
+                    .col(ColumnDef::new(Game::StartedBy).big_unsigned().not_null())
+                    .col(
+                        ColumnDef::new(Game::StartedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Game::EndedAt).timestamp_with_time_zone())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(Game::Table, Game::GuildId)
+                            .to(Guild::Table, Guild::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(Game::Table, Game::StartedBy)
+                            .to(User::Table, User::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Game::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum Game {
+    Table,
+    Id,
+    GuildId,
+    ChannelId,
+    StartedBy,
+    StartedAt,
+    EndedAt,
+}
@@ -0,0 +1,77 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20240205_000001_create_user_table::User;
+use crate::m20240205_000004_create_message_table::Message;
+use crate::m20261018_000001_create_game_table::Game;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(GameQuestion::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(GameQuestion::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(GameQuestion::GameId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(GameQuestion::Position).integer().not_null())
+                    .col(ColumnDef::new(GameQuestion::MessageId).big_unsigned())
+                    .col(ColumnDef::new(GameQuestion::AuthorId).big_unsigned())
+                    .col(
+                        ColumnDef::new(GameQuestion::AskedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(GameQuestion::Table, GameQuestion::GameId)
+                            .to(Game::Table, Game::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(GameQuestion::Table, GameQuestion::MessageId)
+                            .to(Message::Table, Message::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(GameQuestion::Table, GameQuestion::AuthorId)
+                            .to(User::Table, User::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(GameQuestion::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum GameQuestion {
+    Table,
+    Id,
+    GameId,
+    Position,
+    MessageId,
+    AuthorId,
+    AskedAt,
+    Kind,
+}
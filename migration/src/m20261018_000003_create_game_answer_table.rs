@@ -0,0 +1,70 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20240205_000001_create_user_table::User;
+use crate::m20261018_000002_create_game_question_table::GameQuestion;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(GameAnswer::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(GameAnswer::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(GameAnswer::GameQuestionId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(GameAnswer::UserId).big_unsigned().not_null())
+                    .col(ColumnDef::new(GameAnswer::GuessedUserId).big_unsigned())
+                    .col(ColumnDef::new(GameAnswer::Correct).boolean().not_null())
+                    .col(
+                        ColumnDef::new(GameAnswer::ResponseTimeMs)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(GameAnswer::Table, GameAnswer::GameQuestionId)
+                            .to(GameQuestion::Table, GameQuestion::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(GameAnswer::Table, GameAnswer::UserId)
+                            .to(User::Table, User::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(GameAnswer::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum GameAnswer {
+    Table,
+    Id,
+    GameQuestionId,
+    UserId,
+    GuessedUserId,
+    Correct,
+    ResponseTimeMs,
+    Points,
+}
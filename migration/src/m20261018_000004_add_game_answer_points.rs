@@ -0,0 +1,36 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20261018_000003_create_game_answer_table::GameAnswer;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GameAnswer::Table)
+                    .add_column(
+                        ColumnDef::new(GameAnswer::Points)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GameAnswer::Table)
+                    .drop_column(GameAnswer::Points)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,33 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20240205_000004_create_message_table::Message;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Message::Table)
+                    .add_column(ColumnDef::new(Message::EditedAt).timestamp_with_time_zone())
+                    .add_column(ColumnDef::new(Message::DeletedAt).timestamp_with_time_zone())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Message::Table)
+                    .drop_column(Message::EditedAt)
+                    .drop_column(Message::DeletedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
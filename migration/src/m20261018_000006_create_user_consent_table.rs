@@ -0,0 +1,67 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20240205_000001_create_user_table::User;
+use crate::m20240205_000002_create_guild_table::Guild;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .primary_key(
+                        Index::create()
+                            .col(UserConsent::UserId)
+                            .col(UserConsent::GuildId),
+                    )
+                    .table(UserConsent::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(UserConsent::UserId)
+                            .big_unsigned()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UserConsent::GuildId)
+                            .big_unsigned()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(UserConsent::OptedOut).boolean().not_null())
+                    .col(
+                        ColumnDef::new(UserConsent::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(UserConsent::Table, UserConsent::UserId)
+                            .to(User::Table, User::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(UserConsent::Table, UserConsent::GuildId)
+                            .to(Guild::Table, Guild::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserConsent::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum UserConsent {
+    Table,
+    UserId,
+    GuildId,
+    OptedOut,
+    UpdatedAt,
+}
@@ -0,0 +1,63 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20240205_000001_create_user_table::User;
+use crate::m20240205_000002_create_guild_table::Guild;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .primary_key(
+                        Index::create()
+                            .col(GuildMember::UserId)
+                            .col(GuildMember::GuildId),
+                    )
+                    .table(GuildMember::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(GuildMember::UserId)
+                            .big_unsigned()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GuildMember::GuildId)
+                            .big_unsigned()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(GuildMember::JoinedAt).timestamp_with_time_zone())
+                    .col(ColumnDef::new(GuildMember::LeftAt).timestamp_with_time_zone())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(GuildMember::Table, GuildMember::UserId)
+                            .to(User::Table, User::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(GuildMember::Table, GuildMember::GuildId)
+                            .to(Guild::Table, Guild::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(GuildMember::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum GuildMember {
+    Table,
+    UserId,
+    GuildId,
+    JoinedAt,
+    LeftAt,
+}
@@ -0,0 +1,48 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20240407_000001_create_user_emoji_table::UserEmoji;
+
+const INDEX_NAME: &str = "idx-user_emoji-guild_id-emoji";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Emoji claimed by several users of a guild stay with the user who has the smallest id.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"DELETE FROM "user_emoji" AS "claim"
+                USING "user_emoji" AS "first_claim"
+                WHERE "claim"."guild_id" = "first_claim"."guild_id"
+                AND "claim"."emoji" = "first_claim"."emoji"
+                AND "claim"."user_id" > "first_claim"."user_id""#,
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(INDEX_NAME)
+                    .table(UserEmoji::Table)
+                    .col(UserEmoji::GuildId)
+                    .col(UserEmoji::Emoji)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name(INDEX_NAME)
+                    .table(UserEmoji::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,36 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20261018_000002_create_game_question_table::GameQuestion;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GameQuestion::Table)
+                    .add_column(
+                        ColumnDef::new(GameQuestion::Kind)
+                            .string()
+                            .not_null()
+                            .default("author"),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GameQuestion::Table)
+                    .drop_column(GameQuestion::Kind)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,78 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20240205_000002_create_guild_table::Guild;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(QuoteFilter::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(QuoteFilter::GuildId)
+                            .big_unsigned()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(QuoteFilter::SkipLinks)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .col(
+                        ColumnDef::new(QuoteFilter::SkipCommands)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .col(
+                        ColumnDef::new(QuoteFilter::SkipEmojiOnly)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .col(
+                        ColumnDef::new(QuoteFilter::SkipDuplicates)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .col(
+                        ColumnDef::new(QuoteFilter::MinimumWords)
+                            .integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(QuoteFilter::Table, QuoteFilter::GuildId)
+                            .to(Guild::Table, Guild::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(QuoteFilter::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum QuoteFilter {
+    Table,
+    GuildId,
+    SkipLinks,
+    SkipCommands,
+    SkipEmojiOnly,
+    SkipDuplicates,
+    MinimumWords,
+}
@@ -0,0 +1,63 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20240205_000002_create_guild_table::Guild;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(QuoteFilterPattern::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(QuoteFilterPattern::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(QuoteFilterPattern::GuildId)
+                            .big_unsigned()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(QuoteFilterPattern::Pattern)
+                            .string()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(QuoteFilterPattern::Table, QuoteFilterPattern::GuildId)
+                            .to(Guild::Table, Guild::Id),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx-quote_filter_pattern-guild_id-pattern")
+                            .col(QuoteFilterPattern::GuildId)
+                            .col(QuoteFilterPattern::Pattern)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(QuoteFilterPattern::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum QuoteFilterPattern {
+    Table,
+    Id,
+    GuildId,
+    Pattern,
+}
@@ -0,0 +1,92 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20240205_000002_create_guild_table::Guild;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(GuildConfig::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(GuildConfig::GuildId)
+                            .big_unsigned()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(GuildConfig::Questions)
+                            .integer()
+                            .not_null()
+                            .default(10),
+                    )
+                    .col(
+                        ColumnDef::new(GuildConfig::MinimumQuoteLength)
+                            .integer()
+                            .not_null()
+                            .default(10),
+                    )
+                    .col(
+                        ColumnDef::new(GuildConfig::Choices)
+                            .integer()
+                            .not_null()
+                            .default(13),
+                    )
+                    .col(
+                        ColumnDef::new(GuildConfig::AnswerTimeoutSecs)
+                            .integer()
+                            .not_null()
+                            .default(15),
+                    )
+                    .col(
+                        ColumnDef::new(GuildConfig::StartDelaySecs)
+                            .integer()
+                            .not_null()
+                            .default(5),
+                    )
+                    .col(
+                        ColumnDef::new(GuildConfig::RevealSecs)
+                            .integer()
+                            .not_null()
+                            .default(5),
+                    )
+                    .col(
+                        ColumnDef::new(GuildConfig::ScrapeIntervalSecs)
+                            .integer()
+                            .not_null()
+                            .default(60),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(GuildConfig::Table, GuildConfig::GuildId)
+                            .to(Guild::Table, Guild::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(GuildConfig::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum GuildConfig {
+    Table,
+    GuildId,
+    Questions,
+    MinimumQuoteLength,
+    Choices,
+    AnswerTimeoutSecs,
+    StartDelaySecs,
+    RevealSecs,
+    ScrapeIntervalSecs,
+}
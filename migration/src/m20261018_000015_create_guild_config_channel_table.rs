@@ -0,0 +1,59 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20240205_000002_create_guild_table::Guild;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .primary_key(
+                        Index::create()
+                            .col(GuildConfigChannel::GuildId)
+                            .col(GuildConfigChannel::ChannelId),
+                    )
+                    .table(GuildConfigChannel::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(GuildConfigChannel::GuildId)
+                            .big_unsigned()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GuildConfigChannel::ChannelId)
+                            .big_unsigned()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GuildConfigChannel::Allowed)
+                            .boolean()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(GuildConfigChannel::Table, GuildConfigChannel::GuildId)
+                            .to(Guild::Table, Guild::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(GuildConfigChannel::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum GuildConfigChannel {
+    Table,
+    GuildId,
+    ChannelId,
+    Allowed,
+}
@@ -0,0 +1,59 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20240205_000002_create_guild_table::Guild;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .primary_key(
+                        Index::create()
+                            .col(GuildScrapeChannel::GuildId)
+                            .col(GuildScrapeChannel::ChannelId),
+                    )
+                    .table(GuildScrapeChannel::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(GuildScrapeChannel::GuildId)
+                            .big_unsigned()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GuildScrapeChannel::ChannelId)
+                            .big_unsigned()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GuildScrapeChannel::Scraped)
+                            .boolean()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(GuildScrapeChannel::Table, GuildScrapeChannel::GuildId)
+                            .to(Guild::Table, Guild::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(GuildScrapeChannel::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum GuildScrapeChannel {
+    Table,
+    GuildId,
+    ChannelId,
+    Scraped,
+}
@@ -0,0 +1,319 @@
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+use std::time::Duration;
+
+use itertools::Itertools;
+use serenity::builder::*;
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+use tracing::error;
+
+use crate::commands::can_manage_guild;
+use crate::commands::error::CommandError;
+use crate::database::Database;
+use crate::database::guild_config::GuildConfig;
+use crate::game::options::{
+    ANSWER_TIMEOUT_SECS_RANGE, CHOICES_RANGE, GameOptionError, MINIMUM_QUOTE_LENGTH_RANGE,
+    PAUSE_SECS_RANGE, QUESTIONS_RANGE, check_range,
+};
+use crate::scrapper::{SCRAPE_INTERVAL_SECS_RANGE, Scrapper};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error(transparent)]
+    InvalidOption(#[from] GameOptionError),
+    #[error("{0} has no rule of its own")]
+    UnlistedChannel(Mention),
+}
+
+pub async fn run(
+    database: Arc<Database>,
+    scrapper: Arc<Scrapper>,
+    ctx: &Context,
+    command_interaction: &CommandInteraction,
+) -> Result<(), CommandError> {
+    let Some(guild_id) = command_interaction.guild_id else {
+        return Err(CommandError::NotInAGuild);
+    };
+
+    if !can_manage_guild(command_interaction) {
+        return Err(CommandError::ManageGuildRequired);
+    }
+
+    let Some(subcommand) = command_interaction.data.options.first() else {
+        return Ok(());
+    };
+    let CommandDataOptionValue::SubCommand(options) = &subcommand.value else {
+        return Ok(());
+    };
+
+    let channel_id =
+        options
+            .iter()
+            .find_map(|option| match (option.name.as_str(), &option.value) {
+                ("channel", CommandDataOptionValue::Channel(channel_id)) => Some(*channel_id),
+                _ => None,
+            });
+
+    let content = match (subcommand.name.as_str(), channel_id) {
+        ("show", _) => describe(&database.get_guild_config(guild_id).await?),
+        ("set", _) => {
+            let mut config = database.get_guild_config(guild_id).await?;
+
+            for option in options {
+                let CommandDataOptionValue::Integer(value) = option.value else {
+                    continue;
+                };
+
+                match option.name.as_str() {
+                    "questions" => {
+                        config.n_questions = check_range("questions", value, QUESTIONS_RANGE)
+                            .map_err(ConfigError::from)?
+                    }
+                    "min_length" => {
+                        config.minimum_quote_length =
+                            check_range("min_length", value, MINIMUM_QUOTE_LENGTH_RANGE)
+                                .map_err(ConfigError::from)?
+                    }
+                    "choices" => {
+                        config.n_choices = check_range("choices", value, CHOICES_RANGE)
+                            .map_err(ConfigError::from)?
+                    }
+                    "timeout" => {
+                        config.answer_timeout =
+                            seconds("timeout", value, ANSWER_TIMEOUT_SECS_RANGE)?
+                    }
+                    "start_delay" => {
+                        config.start_delay = seconds("start_delay", value, PAUSE_SECS_RANGE)?
+                    }
+                    "reveal_time" => {
+                        config.reveal_duration = seconds("reveal_time", value, PAUSE_SECS_RANGE)?
+                    }
+                    "scrape_interval" => {
+                        config.scrape_interval =
+                            seconds("scrape_interval", value, SCRAPE_INTERVAL_SECS_RANGE)?
+                    }
+                    _ => {}
+                }
+            }
+
+            database.save_guild_config(guild_id, &config).await?;
+
+            describe(&config)
+        }
+        ("allow-channel", Some(channel_id)) => {
+            database
+                .save_config_channel(guild_id, channel_id, true)
+                .await?;
+
+            format!(
+                "Games will only quote the allowed channels, {} included",
+                channel_id.mention()
+            )
+        }
+        ("deny-channel", Some(channel_id)) => {
+            database
+                .save_config_channel(guild_id, channel_id, false)
+                .await?;
+
+            format!("Games will never quote {}", channel_id.mention())
+        }
+        ("scrape-channel", Some(channel_id)) => {
+            database
+                .save_scrape_channel(guild_id, channel_id, true)
+                .await?;
+            refresh_channel(scrapper, ctx, channel_id);
+
+            format!("Messages of {} will be saved", channel_id.mention())
+        }
+        ("ignore-channel", Some(channel_id)) => {
+            database
+                .save_scrape_channel(guild_id, channel_id, false)
+                .await?;
+            refresh_channel(scrapper, ctx, channel_id);
+
+            format!(
+                "Messages of {} will not be saved anymore, the saved ones are being deleted",
+                channel_id.mention()
+            )
+        }
+        ("reset-channel", Some(channel_id)) => {
+            let quote_rule = database.remove_config_channel(guild_id, channel_id).await?;
+            let scrape_rule = database.remove_scrape_channel(guild_id, channel_id).await?;
+
+            if !quote_rule && !scrape_rule {
+                return Err(ConfigError::UnlistedChannel(channel_id.mention()).into());
+            }
+            if scrape_rule {
+                refresh_channel(scrapper, ctx, channel_id);
+            }
+
+            format!(
+                "{} follows the defaults again, NSFW channels are not saved",
+                channel_id.mention()
+            )
+        }
+        _ => return Err(CommandError::UnknownCommand),
+    };
+
+    let message = CreateInteractionResponse::Message(
+        CreateInteractionResponseMessage::new()
+            .content(content)
+            .allowed_mentions(CreateAllowedMentions::new())
+            .ephemeral(true),
+    );
+
+    command_interaction.create_response(&ctx, message).await?;
+
+    Ok(())
+}
+
+/// Scraping or purging a channel takes a while, it goes on after the response.
+fn refresh_channel(scrapper: Arc<Scrapper>, ctx: &Context, channel_id: ChannelId) {
+    let ctx = ctx.clone();
+
+    tokio::spawn(async move {
+        if let Err(e) = scrapper.refresh_channel(&ctx, channel_id).await {
+            error!("Refreshing channel {} failed: {}", channel_id, e);
+        }
+    });
+}
+
+fn seconds(
+    name: &'static str,
+    value: i64,
+    range: RangeInclusive<u32>,
+) -> Result<Duration, ConfigError> {
+    Ok(Duration::from_secs(check_range(name, value, range)?.into()))
+}
+
+fn describe(config: &GuildConfig) -> String {
+    let channels = |channels: &[ChannelId], empty: &str| match channels.is_empty() {
+        true => empty.to_string(),
+        false => channels
+            .iter()
+            .map(|channel_id| channel_id.mention())
+            .join(", "),
+    };
+
+    format!(
+        "**Game defaults**\n\
+        Questions: {}\n\
+        Minimum quote length: {}\n\
+        Possible responses: {}\n\
+        Seconds to answer: {}\n\
+        Seconds before the first question: {}\n\
+        Seconds showing each answer: {}\n\
+        Seconds between catch-ups of missed messages: {}\n\
+        Allowed channels: {}\n\
+        Denied channels: {}\n\
+        Saved channels, even NSFW: {}\n\
+        Ignored channels: {}",
+        config.n_questions,
+        config.minimum_quote_length,
+        config.n_choices,
+        config.answer_timeout.as_secs(),
+        config.start_delay.as_secs(),
+        config.reveal_duration.as_secs(),
+        config.scrape_interval.as_secs(),
+        channels(&config.allowed_channels, "all"),
+        channels(&config.denied_channels, "none"),
+        channels(&config.scraped_channels, "none"),
+        channels(&config.ignored_channels, "none"),
+    )
+}
+
+fn integer_option(
+    name: &str,
+    description: &str,
+    range: RangeInclusive<u32>,
+) -> CreateCommandOption {
+    CreateCommandOption::new(CommandOptionType::Integer, name, description)
+        .min_int_value(*range.start() as u64)
+        .max_int_value(*range.end() as u64)
+}
+
+fn channel_subcommand(name: &str, description: &str) -> CreateCommandOption {
+    CreateCommandOption::new(CommandOptionType::SubCommand, name, description).add_sub_option(
+        CreateCommandOption::new(
+            CommandOptionType::Channel,
+            "channel",
+            "A channel, its threads are included",
+        )
+        .channel_types(vec![
+            ChannelType::Text,
+            ChannelType::News,
+            ChannelType::Forum,
+            ChannelType::PublicThread,
+            ChannelType::PrivateThread,
+            ChannelType::NewsThread,
+        ])
+        .required(true),
+    )
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("whosaid-config")
+        .description("Change the defaults of the games of this server")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "show",
+            "Show the defaults of this server",
+        ))
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "set", "Change the defaults")
+                .add_sub_option(integer_option(
+                    "questions",
+                    "Number of questions",
+                    QUESTIONS_RANGE,
+                ))
+                .add_sub_option(integer_option(
+                    "min_length",
+                    "Minimum quote length",
+                    MINIMUM_QUOTE_LENGTH_RANGE,
+                ))
+                .add_sub_option(integer_option(
+                    "choices",
+                    "Number of possible responses",
+                    CHOICES_RANGE,
+                ))
+                .add_sub_option(integer_option(
+                    "timeout",
+                    "Seconds to answer",
+                    ANSWER_TIMEOUT_SECS_RANGE,
+                ))
+                .add_sub_option(integer_option(
+                    "start_delay",
+                    "Seconds before the first question, for games without a lobby",
+                    PAUSE_SECS_RANGE,
+                ))
+                .add_sub_option(integer_option(
+                    "reveal_time",
+                    "Seconds each answer is shown before the next question",
+                    PAUSE_SECS_RANGE,
+                ))
+                .add_sub_option(integer_option(
+                    "scrape_interval",
+                    "Seconds between catch-ups of the messages the bot missed",
+                    SCRAPE_INTERVAL_SECS_RANGE,
+                )),
+        )
+        .add_option(channel_subcommand(
+            "allow-channel",
+            "Only quote allowed channels, any channel can be quoted while none is allowed",
+        ))
+        .add_option(channel_subcommand("deny-channel", "Never quote a channel"))
+        .add_option(channel_subcommand(
+            "scrape-channel",
+            "Save the messages of a channel, NSFW channels are not saved by default",
+        ))
+        .add_option(channel_subcommand(
+            "ignore-channel",
+            "Never save the messages of a channel, and delete the saved ones",
+        ))
+        .add_option(channel_subcommand(
+            "reset-channel",
+            "Remove the rules of a channel, so it follows the defaults again",
+        ))
+}
@@ -1,79 +1,239 @@
 use std::sync::Arc;
-use std::time::Duration;
 
-use serenity::all::CreateQuickModal;
 use serenity::builder::*;
 use serenity::model::prelude::*;
 use serenity::prelude::*;
+use serenity::utils::parse_emoji;
 
+use crate::commands::can_manage_guild;
+use crate::commands::error::CommandError;
 use crate::database::Database;
+use crate::database::error::DatabaseError;
 
 #[derive(Debug, thiserror::Error)]
 pub enum EmojiError {
     #[error("You need to be in a guild to set an emoji")]
     NotInAGuild(),
-    #[error("Could not find emoji")]
-    UnknownEmoji(),
+    #[error("Could not find emoji `{0}`, use an emoji of this server or a standard emoji")]
+    UnknownEmoji(String),
+    #[error("{0} is already claimed by {1}")]
+    AlreadyClaimed(String, Mention),
+    #[error("You need the Manage Server permission to change the emoji of someone else")]
+    MissingPermission(),
 }
 
 pub async fn run(
     database: Arc<Database>,
     ctx: &Context,
     command_interaction: &CommandInteraction,
-) -> anyhow::Result<()> {
-    let modal = CreateQuickModal::new("Your emoji")
-        .timeout(Duration::from_secs(60))
-        .field(
-            CreateInputText::new(InputTextStyle::Short, "Emoji", "emoji").value(":lj:".to_owned()),
-        );
-    let response = command_interaction.quick_modal(ctx, modal).await?.unwrap();
+) -> Result<(), CommandError> {
+    let Some(guild_id) = command_interaction.guild_id else {
+        return Err(EmojiError::NotInAGuild().into());
+    };
 
-    let inputs = response.inputs;
+    let Some(subcommand) = command_interaction.data.options.first() else {
+        return Ok(());
+    };
+    let CommandDataOptionValue::SubCommand(options) = &subcommand.value else {
+        return Ok(());
+    };
 
-    let mut emoji_name: &str = &inputs[0];
+    let user_id = options
+        .iter()
+        .find_map(|option| match (option.name.as_str(), &option.value) {
+            ("user", CommandDataOptionValue::User(user_id)) => Some(*user_id),
+            _ => None,
+        })
+        .unwrap_or(command_interaction.user.id);
+
+    if user_id != command_interaction.user.id && !can_manage_guild(command_interaction) {
+        return Err(EmojiError::MissingPermission().into());
+    }
 
-    emoji_name = emoji_name.strip_prefix(':').unwrap_or(emoji_name);
-    emoji_name = emoji_name.strip_suffix(':').unwrap_or(emoji_name);
+    let content = match subcommand.name.as_str() {
+        "set" => {
+            let input = options
+                .iter()
+                .find_map(|option| match (option.name.as_str(), &option.value) {
+                    ("emoji", CommandDataOptionValue::String(input)) => Some(input.as_str()),
+                    _ => None,
+                })
+                .unwrap_or_default();
+
+            set_emoji(
+                &database,
+                ctx,
+                command_interaction,
+                guild_id,
+                user_id,
+                input,
+            )
+            .await?
+        }
+        "remove" => {
+            database.remove_user_emoji(user_id, guild_id).await?;
+
+            format!("The emoji of {} has been removed", user_id.mention())
+        }
+        _ => return Err(CommandError::UnknownCommand),
+    };
 
-    let guild = command_interaction
-        .guild_id
-        .unwrap()
-        .to_partial_guild(&ctx.http)
-        .await?;
+    let message = CreateInteractionResponse::Message(
+        CreateInteractionResponseMessage::new().content(content),
+    );
 
-    let emoji = guild
-        .emojis
-        .iter()
-        .find(|&(_, emoji)| emoji.name == *emoji_name);
+    command_interaction.create_response(&ctx, message).await?;
+
+    Ok(())
+}
 
-    if emoji.is_none() {
-        return Err(EmojiError::UnknownEmoji().into());
+async fn set_emoji(
+    database: &Database,
+    ctx: &Context,
+    command_interaction: &CommandInteraction,
+    guild_id: GuildId,
+    user_id: UserId,
+    input: &str,
+) -> Result<String, CommandError> {
+    let emoji = find_emoji(ctx, guild_id, input)
+        .await?
+        .ok_or_else(|| EmojiError::UnknownEmoji(input.to_string()))?;
+
+    let user = match command_interaction.data.resolved.users.get(&user_id) {
+        Some(user) => user,
+        None => &command_interaction.user,
+    };
+    database.save_user(user).await?;
+
+    match database.save_user_emoji(user_id, guild_id, &emoji).await {
+        Ok(_) => Ok(format!(
+            "The emoji of {} has been set to {}",
+            user_id.mention(),
+            emoji
+        )),
+        Err(DatabaseError::EmojiClaimed(owner)) => {
+            Err(EmojiError::AlreadyClaimed(emoji, owner.mention()).into())
+        }
+        Err(err) => Err(err.into()),
     }
+}
 
-    if command_interaction.guild_id.is_none() {
-        return Err(EmojiError::NotInAGuild().into());
+/// Resolves the input to the representation of a custom emoji of the guild, given as `<:name:id>`,
+/// `:name:` or `name`, or to a unicode emoji.
+async fn find_emoji(
+    ctx: &Context,
+    guild_id: GuildId,
+    input: &str,
+) -> Result<Option<String>, SerenityError> {
+    let input = input.trim();
+
+    if is_unicode_emoji(input) {
+        return Ok(Some(input.to_string()));
     }
 
-    let (_, emoji) = emoji.unwrap();
+    let guild_emojis = guild_id.emojis(&ctx.http).await?;
 
-    database
-        .save_user_emoji(
-            command_interaction.user.id,
-            command_interaction.guild_id.unwrap(),
-            &format!("{}", &emoji),
-        )
-        .await?;
+    let emoji = match parse_emoji(input) {
+        Some(identifier) => guild_emojis.iter().find(|emoji| emoji.id == identifier.id),
+        None => {
+            let name = input.strip_prefix(':').unwrap_or(input);
+            let name = name.strip_suffix(':').unwrap_or(name);
 
-    let message = CreateInteractionResponse::Message(
-        CreateInteractionResponseMessage::new()
-            .content(format!("Your emoji has been set to {}", emoji)),
-    );
+            guild_emojis.iter().find(|emoji| emoji.name == name)
+        }
+    };
 
-    response.interaction.create_response(&ctx, message).await?;
+    Ok(emoji.map(|emoji| emoji.to_string()))
+}
 
-    Ok(())
+/// A single emoji of the Unicode list, in its fully-qualified form as emoji pickers send it, so
+/// that text symbols such as `©` are not taken for one.
+fn is_unicode_emoji(input: &str) -> bool {
+    emojis::get(input).is_some_and(|emoji| emoji.as_str() == input)
 }
 
 pub fn register() -> CreateCommand {
-    CreateCommand::new("emoji").description("Set your emoji for whosaid")
+    CreateCommand::new("emoji")
+        .description("Set your emoji for whosaid")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "set", "Set an emoji")
+                .add_sub_option(
+                    CreateCommandOption::new(
+                        CommandOptionType::String,
+                        "emoji",
+                        "An emoji of this server or a standard emoji",
+                    )
+                    .required(true),
+                )
+                .add_sub_option(CreateCommandOption::new(
+                    CommandOptionType::User,
+                    "user",
+                    "Member to set the emoji of, requires the Manage Server permission",
+                )),
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "remove", "Remove an emoji")
+                .add_sub_option(CreateCommandOption::new(
+                    CommandOptionType::User,
+                    "user",
+                    "Member to remove the emoji of, requires the Manage Server permission",
+                )),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_emoji_are_accepted() {
+        assert!(is_unicode_emoji("😀"));
+        assert!(is_unicode_emoji("❤\u{FE0F}"));
+        assert!(is_unicode_emoji("⭐"));
+        assert!(is_unicode_emoji("©\u{FE0F}"));
+    }
+
+    #[test]
+    fn composed_emoji_are_accepted() {
+        // Family, joined with zero width joiners.
+        assert!(is_unicode_emoji("👨\u{200D}👩\u{200D}👧"));
+        // Thumbs up with a skin tone.
+        assert!(is_unicode_emoji("👍🏽"));
+        // Flag of France, from two regional indicators.
+        assert!(is_unicode_emoji("🇫🇷"));
+        // Flag of England, from tag characters.
+        assert!(is_unicode_emoji(
+            "🏴\u{E0067}\u{E0062}\u{E0065}\u{E006E}\u{E0067}\u{E007F}"
+        ));
+    }
+
+    #[test]
+    fn keycaps_are_accepted() {
+        assert!(is_unicode_emoji("1\u{FE0F}\u{20E3}"));
+        assert!(is_unicode_emoji("#\u{FE0F}\u{20E3}"));
+    }
+
+    #[test]
+    fn text_is_rejected() {
+        assert!(!is_unicode_emoji(""));
+        assert!(!is_unicode_emoji("a"));
+        assert!(!is_unicode_emoji("1"));
+        assert!(!is_unicode_emoji("😀 hi"));
+        assert!(!is_unicode_emoji("<:custom:123>"));
+        assert!(!is_unicode_emoji("\u{200D}\u{FE0F}"));
+        assert!(!is_unicode_emoji("a😀"));
+    }
+
+    #[test]
+    fn symbols_are_rejected() {
+        assert!(!is_unicode_emoji("→"));
+        assert!(!is_unicode_emoji("©"));
+    }
+
+    #[test]
+    fn several_emoji_are_rejected() {
+        assert!(!is_unicode_emoji("😀😀"));
+        assert!(!is_unicode_emoji("⌚⌚"));
+        assert!(!is_unicode_emoji(&"😀".repeat(17)));
+    }
 }
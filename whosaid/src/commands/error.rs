@@ -0,0 +1,102 @@
+use serenity::builder::*;
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+use tracing::{error, warn};
+
+use crate::commands::config::ConfigError;
+use crate::commands::emoji::EmojiError;
+use crate::commands::filters::FilterError;
+use crate::database::error::DatabaseError;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CommandError {
+    #[error("This command can only be used in a server")]
+    NotInAGuild,
+    #[error("This command is not implemented :(")]
+    UnknownCommand,
+    #[error("A game is already running in this channel, stop it with /whosaid-stop")]
+    GameAlreadyRunning,
+    #[error("No game is running in this channel")]
+    NoGameRunning,
+    #[error("Only the player who started the game or members who can manage messages can stop it")]
+    NotAllowedToStopGame,
+    /// The user closed the modal or did not submit it in time.
+    #[error("The form was dismissed")]
+    ModalDismissed,
+    #[error("You need the Manage Server permission to use this command")]
+    ManageGuildRequired,
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error(transparent)]
+    Emoji(#[from] EmojiError),
+    #[error(transparent)]
+    Filter(#[from] FilterError),
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+    #[error(transparent)]
+    Discord(#[from] SerenityError),
+}
+
+impl CommandError {
+    /// Message shown to the user, `None` when the failure needs no reply.
+    pub fn user_message(&self) -> Option<String> {
+        match self {
+            Self::ModalDismissed => None,
+            Self::Database(_) | Self::Discord(_) => {
+                Some("Something went wrong, please try again later".to_string())
+            }
+            error => Some(error.to_string()),
+        }
+    }
+
+    /// Failures caused by the bot or Discord rather than by the user.
+    pub fn is_unexpected(&self) -> bool {
+        matches!(self, Self::Database(_) | Self::Discord(_))
+    }
+
+    /// Logs unexpected failures and tells the user what went wrong, in a reply only they can see.
+    pub async fn report(&self, ctx: &Context, command_interaction: &CommandInteraction) {
+        if self.is_unexpected() {
+            error!("Command failed: {}", self);
+        }
+
+        let Some(content) = self.user_message() else {
+            return;
+        };
+
+        let response = CreateInteractionResponse::Message(
+            CreateInteractionResponseMessage::new()
+                .content(&content)
+                .ephemeral(true),
+        );
+
+        // Commands that failed after answering can only be followed up.
+        if command_interaction
+            .create_response(ctx, response)
+            .await
+            .is_ok()
+        {
+            return;
+        }
+
+        // The first followup of a deferred command replaces its loading response and stays public
+        // like it, the loading response is deleted first as in `respond_ephemeral`. A response the
+        // command already filled in is kept.
+        if let Ok(message) = command_interaction.get_response(ctx).await
+            && message
+                .flags
+                .is_some_and(|flags| flags.contains(MessageFlags::LOADING))
+            && let Err(e) = command_interaction.delete_response(ctx).await
+        {
+            warn!("Deleting the deferred response failed: {}", e);
+        }
+
+        let followup = CreateInteractionResponseFollowup::new()
+            .content(content)
+            .ephemeral(true);
+
+        if let Err(e) = command_interaction.create_followup(ctx, followup).await {
+            warn!("Reporting the failure to the user failed: {}", e);
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+use itertools::Itertools;
+use serenity::builder::*;
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::commands::can_manage_guild;
+use crate::commands::error::CommandError;
+use crate::database::Database;
+use crate::database::quote_filter::QuoteFilters;
+use crate::game::options::{GameOptionError, check_range};
+
+const MINIMUM_WORDS_RANGE: RangeInclusive<u32> = 1..=20;
+const MAX_PATTERN_LENGTH: usize = 200;
+/// Every pattern is matched against every candidate quote, keep them few.
+const MAX_PATTERNS: usize = 25;
+
+#[derive(Debug, thiserror::Error)]
+pub enum FilterError {
+    #[error(transparent)]
+    InvalidOption(#[from] GameOptionError),
+    #[error("``{0}`` is not a valid regular expression")]
+    InvalidPattern(String),
+    #[error("Patterns can be at most {MAX_PATTERN_LENGTH} characters long")]
+    PatternTooLong,
+    #[error("A server can have at most {MAX_PATTERNS} patterns, remove one first")]
+    TooManyPatterns,
+    #[error("There is no pattern ``{0}``")]
+    UnknownPattern(String),
+}
+
+pub async fn run(
+    database: Arc<Database>,
+    ctx: &Context,
+    command_interaction: &CommandInteraction,
+) -> Result<(), CommandError> {
+    let Some(guild_id) = command_interaction.guild_id else {
+        return Err(CommandError::NotInAGuild);
+    };
+
+    if !can_manage_guild(command_interaction) {
+        return Err(CommandError::ManageGuildRequired);
+    }
+
+    let Some(subcommand) = command_interaction.data.options.first() else {
+        return Ok(());
+    };
+    let CommandDataOptionValue::SubCommand(options) = &subcommand.value else {
+        return Ok(());
+    };
+
+    let pattern = options
+        .iter()
+        .find_map(|option| match (option.name.as_str(), &option.value) {
+            ("pattern", CommandDataOptionValue::String(pattern)) => Some(pattern.trim()),
+            _ => None,
+        })
+        .unwrap_or_default();
+
+    let content = match subcommand.name.as_str() {
+        "show" => describe(&database.get_quote_filters(guild_id).await?),
+        "set" => {
+            let mut filters = database.get_quote_filters(guild_id).await?;
+
+            for option in options {
+                match (option.name.as_str(), &option.value) {
+                    ("links", CommandDataOptionValue::Boolean(skip)) => filters.skip_links = *skip,
+                    ("commands", CommandDataOptionValue::Boolean(skip)) => {
+                        filters.skip_commands = *skip
+                    }
+                    ("emoji_only", CommandDataOptionValue::Boolean(skip)) => {
+                        filters.skip_emoji_only = *skip
+                    }
+                    ("duplicates", CommandDataOptionValue::Boolean(skip)) => {
+                        filters.skip_duplicates = *skip
+                    }
+                    ("min_words", CommandDataOptionValue::Integer(words)) => {
+                        filters.minimum_words =
+                            check_range("min_words", *words, MINIMUM_WORDS_RANGE)
+                                .map_err(FilterError::from)?
+                    }
+                    _ => {}
+                }
+            }
+
+            database.save_quote_filters(guild_id, &filters).await?;
+
+            describe(&filters)
+        }
+        "add-pattern" => {
+            if pattern.chars().count() > MAX_PATTERN_LENGTH {
+                return Err(FilterError::PatternTooLong.into());
+            }
+            if database.get_quote_filters(guild_id).await?.patterns.len() >= MAX_PATTERNS {
+                return Err(FilterError::TooManyPatterns.into());
+            }
+            if pattern.is_empty() || !database.is_valid_pattern(pattern).await? {
+                return Err(FilterError::InvalidPattern(pattern.to_string()).into());
+            }
+
+            match database.add_quote_filter_pattern(guild_id, pattern).await? {
+                true => format!("Messages matching ``{pattern}`` will not be quoted anymore"),
+                false => format!("Messages matching ``{pattern}`` are already not quoted"),
+            }
+        }
+        "remove-pattern" => match database
+            .remove_quote_filter_pattern(guild_id, pattern)
+            .await?
+        {
+            true => format!("Messages matching ``{pattern}`` can be quoted again"),
+            false => return Err(FilterError::UnknownPattern(pattern.to_string()).into()),
+        },
+        _ => return Err(CommandError::UnknownCommand),
+    };
+
+    let message = CreateInteractionResponse::Message(
+        CreateInteractionResponseMessage::new()
+            .content(content)
+            .allowed_mentions(CreateAllowedMentions::new())
+            .ephemeral(true),
+    );
+
+    command_interaction.create_response(&ctx, message).await?;
+
+    Ok(())
+}
+
+fn describe(filters: &QuoteFilters) -> String {
+    let skipped = |skip: bool| if skip { "skipped" } else { "kept" };
+    let patterns = match filters.patterns.is_empty() {
+        true => "none".to_string(),
+        false => filters
+            .patterns
+            .iter()
+            .map(|pattern| format!("``{pattern}``"))
+            .join(", "),
+    };
+
+    format!(
+        "**Quote filters**\n\
+        Mostly links: {}\n\
+        Bot commands: {}\n\
+        Emoji only: {}\n\
+        Sent by several people: {}\n\
+        Minimum words: {}\n\
+        Excluded patterns: {}",
+        skipped(filters.skip_links),
+        skipped(filters.skip_commands),
+        skipped(filters.skip_emoji_only),
+        skipped(filters.skip_duplicates),
+        filters.minimum_words,
+        patterns,
+    )
+}
+
+fn pattern_option(description: &str) -> CreateCommandOption {
+    CreateCommandOption::new(CommandOptionType::String, "pattern", description)
+        .max_length(MAX_PATTERN_LENGTH as u16)
+        .required(true)
+}
+
+fn skip_option(name: &str, description: &str) -> CreateCommandOption {
+    CreateCommandOption::new(CommandOptionType::Boolean, name, description)
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("whosaid-filters")
+        .description("Choose which messages of this server can be quoted")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "show",
+            "Show the filters of this server",
+        ))
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "set", "Change the filters")
+                .add_sub_option(skip_option("links", "Skip messages which are mostly links"))
+                .add_sub_option(skip_option(
+                    "commands",
+                    "Skip messages which look like commands for other bots, as !play",
+                ))
+                .add_sub_option(skip_option(
+                    "emoji_only",
+                    "Skip messages made only of emoji",
+                ))
+                .add_sub_option(skip_option(
+                    "duplicates",
+                    "Skip messages several people sent, as copy-pastas",
+                ))
+                .add_sub_option(
+                    CreateCommandOption::new(
+                        CommandOptionType::Integer,
+                        "min_words",
+                        "Words a message needs to be quoted",
+                    )
+                    .min_int_value(*MINIMUM_WORDS_RANGE.start() as u64)
+                    .max_int_value(*MINIMUM_WORDS_RANGE.end() as u64),
+                ),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "add-pattern",
+                "Never quote messages matching a regular expression, whatever their case",
+            )
+            .add_sub_option(pattern_option("A regular expression, as \\bspoilers?\\b")),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "remove-pattern",
+                "Quote messages matching a regular expression again",
+            )
+            .add_sub_option(pattern_option("A pattern shown by /whosaid-filters show")),
+        )
+}
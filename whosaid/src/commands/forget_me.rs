@@ -0,0 +1,82 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use serenity::builder::*;
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::commands::error::CommandError;
+use crate::database::Database;
+
+const CONFIRM_ID: &str = "forget_me_confirm";
+
+pub async fn run(
+    database: Arc<Database>,
+    ctx: &Context,
+    command_interaction: &CommandInteraction,
+) -> Result<(), CommandError> {
+    let guild_id = match command_interaction.guild_id {
+        None => {
+            return Err(CommandError::NotInAGuild);
+        }
+        Some(guild_id) => guild_id,
+    };
+
+    let message = CreateInteractionResponse::Message(
+        CreateInteractionResponseMessage::new()
+            .content(
+                "This removes all your stored messages and your emoji from this server, \
+                and stops storing new ones. This cannot be undone.",
+            )
+            .button(
+                CreateButton::new(CONFIRM_ID)
+                    .label("Forget me")
+                    .style(ButtonStyle::Danger),
+            )
+            .ephemeral(true),
+    );
+
+    command_interaction.create_response(&ctx, message).await?;
+
+    let message = command_interaction.get_response(&ctx).await?;
+
+    let confirmation = message
+        .await_component_interaction(&ctx.shard)
+        .timeout(Duration::from_secs(60))
+        .await;
+
+    let content = match confirmation {
+        Some(interaction) if interaction.data.custom_id == CONFIRM_ID => {
+            interaction
+                .create_response(&ctx, CreateInteractionResponse::Acknowledge)
+                .await?;
+
+            database.save_user(&command_interaction.user).await?;
+            database
+                .set_user_opted_out(command_interaction.user.id, guild_id, true)
+                .await?;
+            database
+                .forget_user(command_interaction.user.id, guild_id)
+                .await?;
+
+            "Everything stored about you on this server has been removed."
+        }
+        _ => "Nothing was removed.",
+    };
+
+    command_interaction
+        .edit_response(
+            &ctx,
+            EditInteractionResponse::new()
+                .content(content)
+                .components(vec![]),
+        )
+        .await?;
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("whosaid-forget-me")
+        .description("Remove your stored messages and emoji and stop storing new ones")
+}
@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Datelike, Days, FixedOffset, Utc};
+use serenity::builder::*;
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::commands::error::CommandError;
+use crate::database::Database;
+use crate::database::game::LeaderboardEntry;
+
+const LEADERBOARD_SIZE: u64 = 10;
+
+pub async fn run(
+    database: Arc<Database>,
+    ctx: &Context,
+    command_interaction: &CommandInteraction,
+) -> Result<(), CommandError> {
+    let guild_id = match command_interaction.guild_id {
+        None => {
+            return Err(CommandError::NotInAGuild);
+        }
+        Some(guild_id) => guild_id,
+    };
+
+    let (month_start, week_start) = period_starts();
+
+    let all_time = database
+        .get_leaderboard(guild_id, None, LEADERBOARD_SIZE)
+        .await?;
+    let monthly = database
+        .get_leaderboard(guild_id, Some(month_start), LEADERBOARD_SIZE)
+        .await?;
+    let weekly = database
+        .get_leaderboard(guild_id, Some(week_start), LEADERBOARD_SIZE)
+        .await?;
+
+    let embed = CreateEmbed::new()
+        .title(":trophy: Leaderboard")
+        .field("All time", format_ranking(&all_time), true)
+        .field("This month", format_ranking(&monthly), true)
+        .field("This week", format_ranking(&weekly), true);
+
+    let message =
+        CreateInteractionResponse::Message(CreateInteractionResponseMessage::new().embed(embed));
+
+    command_interaction.create_response(&ctx, message).await?;
+
+    Ok(())
+}
+
+/// Start of the current calendar month and of the current week (starting on monday), in UTC.
+fn period_starts() -> (DateTime<FixedOffset>, DateTime<FixedOffset>) {
+    let today = Utc::now().date_naive();
+
+    let month_start = today.with_day(1).unwrap_or(today);
+    let week_start = today
+        .checked_sub_days(Days::new(today.weekday().num_days_from_monday().into()))
+        .unwrap_or(today);
+
+    (
+        month_start
+            .and_time(Default::default())
+            .and_utc()
+            .fixed_offset(),
+        week_start
+            .and_time(Default::default())
+            .and_utc()
+            .fixed_offset(),
+    )
+}
+
+fn format_ranking(entries: &[LeaderboardEntry]) -> String {
+    if entries.is_empty() {
+        return "No games played yet".to_string();
+    }
+
+    entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            format!(
+                "{}. {}: {} pts ({} correct)",
+                i + 1,
+                UserId::new(entry.user_id as u64).mention(),
+                entry.points,
+                entry.correct_answers
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("leaderboard").description("Show the whosaid rankings of this server")
+}
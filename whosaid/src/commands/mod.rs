@@ -1,2 +1,21 @@
+use serenity::model::prelude::*;
+
+pub(crate) mod config;
 pub(crate) mod emoji;
+pub(crate) mod error;
+pub(crate) mod filters;
+pub(crate) mod forget_me;
+pub(crate) mod leaderboard;
+pub(crate) mod optin;
+pub(crate) mod optout;
+pub(crate) mod stop;
 pub(crate) mod whosaid;
+
+/// Whether the user running the command has the Manage Server permission in its guild.
+pub(crate) fn can_manage_guild(command_interaction: &CommandInteraction) -> bool {
+    command_interaction
+        .member
+        .as_ref()
+        .and_then(|member| member.permissions)
+        .is_some_and(|permissions| permissions.manage_guild())
+}
@@ -0,0 +1,41 @@
+use std::sync::Arc;
+
+use serenity::builder::*;
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::commands::error::CommandError;
+use crate::database::Database;
+
+pub async fn run(
+    database: Arc<Database>,
+    ctx: &Context,
+    command_interaction: &CommandInteraction,
+) -> Result<(), CommandError> {
+    let guild_id = match command_interaction.guild_id {
+        None => {
+            return Err(CommandError::NotInAGuild);
+        }
+        Some(guild_id) => guild_id,
+    };
+
+    database.save_user(&command_interaction.user).await?;
+    database
+        .set_user_opted_out(command_interaction.user.id, guild_id, false)
+        .await?;
+
+    let message = CreateInteractionResponse::Message(
+        CreateInteractionResponseMessage::new()
+            .content("Your new messages will be stored and can be quoted in games again.")
+            .ephemeral(true),
+    );
+
+    command_interaction.create_response(&ctx, message).await?;
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("whosaid-optin")
+        .description("Allow whosaid to store your messages and quote them in games")
+}
@@ -0,0 +1,47 @@
+use std::sync::Arc;
+
+use serenity::builder::*;
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::commands::error::CommandError;
+use crate::game::registry::GameRegistry;
+
+pub async fn run(
+    games: Arc<GameRegistry>,
+    ctx: &Context,
+    command_interaction: &CommandInteraction,
+) -> Result<(), CommandError> {
+    let channel_id = command_interaction.channel_id;
+
+    let Some(started_by) = games.started_by(channel_id) else {
+        return Err(CommandError::NoGameRunning);
+    };
+
+    let can_manage_messages = command_interaction
+        .member
+        .as_ref()
+        .and_then(|member| member.permissions)
+        .is_some_and(|permissions| permissions.manage_messages());
+
+    if started_by != command_interaction.user.id && !can_manage_messages {
+        return Err(CommandError::NotAllowedToStopGame);
+    }
+
+    if !games.stop(channel_id) {
+        return Err(CommandError::NoGameRunning);
+    }
+
+    let message =
+        CreateInteractionResponse::Message(CreateInteractionResponseMessage::new().content(
+            format!("{} stopped the game", command_interaction.user.mention()),
+        ));
+
+    command_interaction.create_response(&ctx, message).await?;
+
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("whosaid-stop").description("Stop the whosaid game running in this channel")
+}
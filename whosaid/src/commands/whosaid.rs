@@ -1,189 +1,1056 @@
-use serenity::all::CreateQuickModal;
+use chrono::{TimeDelta, Utc};
+use itertools::Itertools;
+use serenity::all::{CreateQuickModal, QuickModalResponse};
 use std::sync::Arc;
 use std::time::Duration;
 
+use crate::commands::error::CommandError;
 use crate::database::Database;
-use crate::game::Game;
+use crate::database::game::NewGameAnswer;
+use crate::database::guild_config::GuildConfig;
+use crate::game::options::{
+    ANSWER_TIMEOUT_SECS_RANGE, CHOICES_PER_QUESTION_RANGE, CHOICES_RANGE, DistractorStrategy,
+    GameOptionError, GameOptions, HINT_AFTER_SECS_RANGE, LOBBY_COUNTDOWN_SECS_RANGE,
+    MINIMUM_QUOTE_LENGTH_RANGE, MissingEmoji, QUESTIONS_RANGE, check_period, check_range,
+    parse_date, parse_number, parse_yes_no,
+};
+use crate::game::question::{Choice, Question, QuestionKind};
+use crate::game::registry::{GameRegistry, RunningGameGuard};
+use crate::game::render;
+use crate::game::score::HINT_PENALTY;
+use crate::game::score::Scoreboard;
+use crate::game::{AnswerError, Game, Phase};
 use serenity::builder::*;
 use serenity::futures::stream::StreamExt;
 use serenity::model::prelude::*;
 use serenity::prelude::*;
 use sqlx::types::chrono::Local;
-use tokio::time::sleep;
+use tokio::time::{Instant, sleep_until};
+
+/// Questions with more possible responses get a select menu, a message holds 25 buttons and the
+/// last row is kept for the game controls.
+const MAX_ANSWER_BUTTONS: usize = 20;
+/// Characters of the label of a button Discord accepts.
+const MAX_BUTTON_LABEL_LENGTH: usize = 80;
+/// Characters of the label of an option of a select menu Discord accepts.
+const MAX_SELECT_LABEL_LENGTH: usize = 100;
+const ANSWER_SELECT_ID: &str = "whosaid_answer";
+const HINT_ID: &str = "whosaid_hint";
+const JOIN_ID: &str = "whosaid_join";
+const START_ID: &str = "whosaid_start";
+
+enum QuestionEvent {
+    Interaction(Box<ComponentInteraction>),
+    /// No hint was shown for `hint_after`.
+    HintTimeout,
+}
 
 pub async fn run(
     database: Arc<Database>,
+    games: Arc<GameRegistry>,
     ctx: &Context,
     command_interaction: &CommandInteraction,
-) -> anyhow::Result<()> {
+) -> Result<(), CommandError> {
     let guild_id = match command_interaction.guild_id {
         None => {
-            return Ok(());
+            return Err(CommandError::NotInAGuild);
         }
         Some(guild_id) => guild_id,
     };
 
-    let modal = CreateQuickModal::new("Game parameters")
-        .timeout(Duration::from_secs(60))
-        .field(
-            CreateInputText::new(InputTextStyle::Short, "Number of questions", "n_question")
-                .value("10".to_owned()),
+    let Some(mut running_game) =
+        games.start(command_interaction.channel_id, command_interaction.user.id)
+    else {
+        return Err(CommandError::GameAlreadyRunning);
+    };
+
+    // Loading the game can take longer than Discord waits for a response, the response is
+    // deferred first. The modal has to be the response of the command, its submission is deferred.
+    let use_modal = wants_modal(command_interaction);
+    if !use_modal {
+        command_interaction.defer(ctx).await?;
+    }
+
+    let config = database.get_guild_config(guild_id).await?;
+    let (mut options, mut errors) = options_from_command(command_interaction, config);
+
+    let modal_interaction = if use_modal {
+        let response =
+            options_from_modal(ctx, command_interaction, &mut options, &mut errors).await?;
+        response.interaction.defer(ctx).await?;
+        Some(response.interaction)
+    } else {
+        None
+    };
+
+    if let Err(error) = check_period(options.from, options.to) {
+        errors.push(error);
+    }
+
+    if !errors.is_empty() {
+        let content = errors.iter().map(|error| format!("- {error}")).join("\n");
+
+        respond_ephemeral(
+            ctx,
+            command_interaction,
+            modal_interaction.as_ref(),
+            format!("Invalid parameters:\n{content}"),
         )
-        .field(
-            CreateInputText::new(
-                InputTextStyle::Short,
-                "Minimum quote length",
-                "minimum_quote_length",
-            )
-            .value("10".to_owned()),
+        .await?;
+        return Ok(());
+    }
+
+    let mut game = Game::load(database.clone(), guild_id, &options).await?;
+
+    let mut announcement = format!(
+        "New game started with {} questions with a minimum quote length of {}",
+        game.questions().len(),
+        options.minimum_quote_length
+    );
+    if let Some(channel_id) = options.channel_id {
+        announcement.push_str(&format!(", quoting {}", channel_id.mention()));
+    }
+
+    let message = EditInteractionResponse::new()
+        .content(announcement)
+        .allowed_mentions(CreateAllowedMentions::new());
+
+    respond(
+        ctx,
+        command_interaction,
+        modal_interaction.as_ref(),
+        message,
+    )
+    .await?;
+
+    database.save_user(&command_interaction.user).await?;
+    let game_id = database
+        .create_game(
+            guild_id,
+            command_interaction.channel_id,
+            command_interaction.user.id,
         )
-        .field(
-            CreateInputText::new(
-                InputTextStyle::Short,
-                "Number of possible responses",
-                "n_most_active_users",
-            )
-            .value("13".to_owned()),
-        );
+        .await?;
 
-    let response = command_interaction.quick_modal(ctx, modal).await?.unwrap();
-    let inputs = response.inputs;
-    let (n_question, minimum_quote_length, n_most_active_users) = (&inputs[0], &inputs[1], &inputs[2]);
+    match game.has_lobby() {
+        true => run_lobby(ctx, command_interaction, &mut game, &mut running_game).await?,
+        false => running_game.sleep(game.phase_duration()).await,
+    }
 
-    let n_questions = n_question.parse::<u32>();
-    let minimum_quote_length = minimum_quote_length.parse::<u32>();
-    let n_most_active_users = n_most_active_users.parse::<u32>();
+    // Message and id of the question being asked, and the players who answered it.
+    let mut asked_question: Option<(Message, i64)> = None;
+    let mut players: Vec<User> = vec![];
 
-    if n_questions.is_err() || minimum_quote_length.is_err() || n_most_active_users.is_err() {
-        let message = CreateInteractionResponse::Message(
-            CreateInteractionResponseMessage::new().content("Invalid parameters"),
-        );
+    loop {
+        if running_game.is_stopped() {
+            game.stop();
+        }
 
-        response.interaction.create_response(&ctx, message).await?;
-        return Ok(());
+        match game.advance(Local::now().fixed_offset()) {
+            Phase::Lobby => {}
+            Phase::Question { index, .. } => {
+                let Some(question) = game.current_question() else {
+                    continue;
+                };
+
+                // Game messages mention players and quote old messages, they must not ping anyone.
+                let message = CreateInteractionResponseFollowup::new()
+                    .content(question_content(
+                        index,
+                        question,
+                        game.shown_hints(),
+                        render::MAX_MESSAGE_LENGTH,
+                    ))
+                    .allowed_mentions(CreateAllowedMentions::new())
+                    .components(question_components(&game));
+
+                let mut message = command_interaction.create_followup(ctx, message).await?;
+                let game_question_id = database
+                    .save_game_question(
+                        game_id,
+                        index,
+                        (!question.generated).then_some(question.quote.id),
+                        question.quote.author_id,
+                        question.kind.name(),
+                        message.timestamp.fixed_offset(),
+                    )
+                    .await?;
+
+                let mut interaction_stream = message
+                    .await_component_interaction(&ctx.shard)
+                    .timeout(game.phase_duration())
+                    .stream();
+
+                players.clear();
+
+                let next_hint = |game: &Game| {
+                    options
+                        .hint_after
+                        .filter(|_| game.has_hint_left())
+                        .map(|hint_after| Instant::now() + hint_after)
+                };
+                let mut next_hint_at = next_hint(&game);
+
+                // A stopped game still reveals the answer of the question being asked.
+                loop {
+                    let event = tokio::select! {
+                        interaction = interaction_stream.next() => {
+                            interaction.map(|interaction| QuestionEvent::Interaction(Box::new(interaction)))
+                        }
+                        _ = running_game.stopped() => None,
+                        _ = sleep_until(next_hint_at.unwrap_or_else(Instant::now)), if next_hint_at.is_some() => {
+                            Some(QuestionEvent::HintTimeout)
+                        }
+                    };
+
+                    let interaction = match event {
+                        None => break,
+                        Some(QuestionEvent::HintTimeout) => {
+                            game.reveal_hint();
+                            next_hint_at = next_hint(&game);
+
+                            message
+                                .edit(
+                                    &ctx,
+                                    EditMessage::new()
+                                        .content(current_question_content(&game, index))
+                                        .allowed_mentions(CreateAllowedMentions::new())
+                                        .components(question_components(&game)),
+                                )
+                                .await?;
+                            continue;
+                        }
+                        Some(QuestionEvent::Interaction(interaction)) => interaction,
+                    };
+
+                    if interaction.data.custom_id == HINT_ID {
+                        let response = if !game.is_player(interaction.user.id) {
+                            ephemeral_message(AnswerError::NotJoined.to_string())
+                        } else if game.reveal_hint().is_some() {
+                            next_hint_at = next_hint(&game);
+
+                            CreateInteractionResponse::UpdateMessage(
+                                CreateInteractionResponseMessage::new()
+                                    .content(current_question_content(&game, index))
+                                    .allowed_mentions(CreateAllowedMentions::new())
+                                    .components(question_components(&game)),
+                            )
+                        } else {
+                            ephemeral_message("There is no hint left")
+                        };
+
+                        interaction.create_response(&ctx, response).await?;
+                        continue;
+                    }
+
+                    let guess = guess(&interaction.data);
+
+                    let content = match game
+                        .submit_answer(interaction.user.id, guess, Local::now().fixed_offset())
+                        .map(|answer| answer.guess)
+                    {
+                        Ok(guess) => {
+                            players.push(interaction.user.clone());
+
+                            format!("You picked **{}**", choice_label(&game, guess))
+                        }
+                        Err(error) => error.to_string(),
+                    };
+
+                    interaction
+                        .create_response(&ctx, ephemeral_message(content))
+                        .await?;
+                }
+
+                asked_question = Some((message, game_question_id));
+            }
+            Phase::Reveal { index } => {
+                let (Some((mut message, game_question_id)), Some(question)) =
+                    (asked_question.take(), game.current_question())
+                else {
+                    continue;
+                };
+
+                let answers: Vec<NewGameAnswer> = game
+                    .answers()
+                    .iter()
+                    .map(|answer| NewGameAnswer {
+                        user_id: answer.user_id,
+                        // Only author questions are answered with a user.
+                        guessed_user_id: answer
+                            .guess
+                            .filter(|_| question.kind == QuestionKind::Author),
+                        correct: answer.correct,
+                        response_time_ms: answer.response_time.num_milliseconds(),
+                        points: answer.points,
+                    })
+                    .collect();
+
+                database.save_users(&players.iter().collect()).await?;
+                database
+                    .save_game_answers(game_question_id, &answers)
+                    .await?;
+
+                message
+                    .edit(
+                        &ctx,
+                        EditMessage::new()
+                            .content(reveal_content(&game, index, question))
+                            .allowed_mentions(CreateAllowedMentions::new())
+                            .components(vec![]),
+                    )
+                    .await?;
+
+                running_game.sleep(game.phase_duration()).await;
+            }
+            Phase::Finished => break,
+        }
     }
 
-    let n_questions = n_questions?;
-    let minimum_quote_length = minimum_quote_length?;
-    let n_most_active_users = n_most_active_users?;
+    database.end_game(game_id).await?;
 
-    let game = Game::new(database, guild_id, n_questions, minimum_quote_length, n_most_active_users).await?;
+    command_interaction
+        .create_followup(
+            ctx,
+            CreateInteractionResponseFollowup::new()
+                .embed(summary_embed(game.scoreboard(), game.is_stopped()))
+                .allowed_mentions(CreateAllowedMentions::new()),
+        )
+        .await?;
 
-    let message = CreateInteractionResponse::Message(
-        CreateInteractionResponseMessage::new().content(format!(
-            "New game started with {} questions with a minimum quote length of {}",
-            n_question, minimum_quote_length
-        )),
-    );
+    Ok(())
+}
 
-    response.interaction.create_response(&ctx, message).await?;
+/// Lets players join until the countdown ends, the starter starts the game or the game is stopped.
+async fn run_lobby(
+    ctx: &Context,
+    command_interaction: &CommandInteraction,
+    game: &mut Game,
+    running_game: &mut RunningGameGuard,
+) -> Result<(), CommandError> {
+    let starter = command_interaction.user.id;
+    let starts_at = Utc::now().timestamp() + game.phase_duration().as_secs() as i64;
+
+    let _ = game.join(starter);
+
+    let mut message = command_interaction
+        .create_followup(
+            ctx,
+            CreateInteractionResponseFollowup::new()
+                .content(lobby_content(game, Some(starts_at)))
+                .allowed_mentions(CreateAllowedMentions::new())
+                .button(
+                    CreateButton::new(JOIN_ID)
+                        .label("Join")
+                        .style(ButtonStyle::Success),
+                )
+                .button(CreateButton::new(START_ID).label("Start now")),
+        )
+        .await?;
 
-    sleep(Duration::from_secs(5)).await;
+    let mut interaction_stream = message
+        .await_component_interaction(&ctx.shard)
+        .timeout(game.phase_duration())
+        .stream();
 
-    let quotes = game.messages();
-    let users = game.users();
+    loop {
+        let interaction = tokio::select! {
+            interaction = interaction_stream.next() => interaction,
+            _ = running_game.stopped() => None,
+        };
+        let Some(interaction) = interaction else {
+            break;
+        };
 
-    for (i, quote) in quotes.iter().enumerate() {
-        let mut message = CreateInteractionResponseFollowup::new().content(format!(
+        let response = match interaction.data.custom_id.as_str() {
+            JOIN_ID => match game.join(interaction.user.id) {
+                Ok(()) => CreateInteractionResponse::UpdateMessage(
+                    CreateInteractionResponseMessage::new()
+                        .content(lobby_content(game, Some(starts_at)))
+                        .allowed_mentions(CreateAllowedMentions::new()),
+                ),
+                Err(error) => ephemeral_message(error.to_string()),
+            },
+            START_ID if interaction.user.id == starter => {
+                interaction
+                    .create_response(&ctx, CreateInteractionResponse::Acknowledge)
+                    .await?;
+                break;
+            }
+            START_ID => ephemeral_message(format!(
+                "Only {} can start the game early",
+                starter.mention()
+            )),
+            _ => continue,
+        };
+
+        interaction.create_response(&ctx, response).await?;
+    }
+
+    message
+        .edit(
+            &ctx,
+            EditMessage::new()
+                .content(lobby_content(game, None))
+                .allowed_mentions(CreateAllowedMentions::new())
+                .components(vec![]),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Lists the players of the lobby, with a live countdown to `starts_at` while it is open.
+fn lobby_content(game: &Game, starts_at: Option<i64>) -> String {
+    let players = game.players().unwrap_or_default();
+    let header = match starts_at {
+        Some(starts_at) => format!("**Lobby**, the game starts <t:{starts_at}:R>"),
+        None => "**Lobby closed**".to_string(),
+    };
+
+    format!(
+        "{header}\nPlayers ({}): {}",
+        players.len(),
+        players.iter().map(|player| player.mention()).join(", ")
+    )
+}
+
+/// The question, within `max_length` characters: the quote, the descriptions of the choices and
+/// the hints share what the rest leaves, so no choice is ever left out.
+fn question_content(
+    index: usize,
+    question: &Question,
+    hints: &[String],
+    max_length: usize,
+) -> String {
+    let described: Vec<&Choice> = question
+        .choices
+        .iter()
+        .filter(|choice| choice.description.is_some())
+        .collect();
+
+    let content = |quote: &str, descriptions: &[String], hints: &[String]| {
+        let mut content = format!(
             r#"
-                Question {}: Who said this ?
-                > {}
-            "#,
-            i + 1,
-            quote.content
-        ));
-
-        for user in users {
-            message =
-                message
-                    .button(CreateButton::new(user.id.to_string())
-                    .label(user.name.to_string()));
+Question {}: {}
+{}
+"#,
+            index + 1,
+            question.kind.prompt(),
+            quote
+        );
+
+        for (choice, description) in described.iter().zip(descriptions) {
+            content.push_str(&format!("**{}** {}\n", choice.label, description));
+        }
+
+        for (i, hint) in hints.iter().enumerate() {
+            content.push_str(&format!(":bulb: Hint {}: {}\n", i + 1, hint));
         }
 
-        let mut message = command_interaction
-            .create_followup(ctx, message).await?;
+        content
+    };
+
+    let texts: Vec<String> = std::iter::once(render::block_quote(&render::truncate(
+        &question.quote.content,
+        render::MAX_QUOTE_LENGTH,
+    )))
+    .chain(described.iter().map(|choice| {
+        render::truncate(
+            choice.description.as_deref().unwrap_or_default(),
+            render::MAX_EXCERPT_LENGTH,
+        )
+    }))
+    .chain(hints.iter().cloned())
+    .collect();
+
+    let fixed_length = content(
+        "",
+        &vec![String::new(); described.len()],
+        &vec![String::new(); hints.len()],
+    )
+    .chars()
+    .count();
+    let texts = render::truncate_all(&texts, max_length.saturating_sub(fixed_length));
+    let (descriptions, hints) = texts[1..].split_at(described.len());
 
-        let mut interaction_stream = message
-            .await_component_interaction(&ctx.shard)
-            .timeout(Duration::from_secs(15))
-            .stream();
+    content(&texts[0], descriptions, hints)
+}
+
+fn current_question_content(game: &Game, index: usize) -> String {
+    game.current_question()
+        .map(|question| {
+            question_content(
+                index,
+                question,
+                game.shown_hints(),
+                render::MAX_MESSAGE_LENGTH,
+            )
+        })
+        .unwrap_or_default()
+}
 
-        let mut responses: Vec<(_, _)> = vec![];
-        
-        while let Some(interaction) = interaction_stream.next().await {
-            let dt = Local::now().signed_duration_since(*message.timestamp);
+/// Possible responses, and a hint button while the question has hints left.
+fn question_components(game: &Game) -> Vec<CreateActionRow> {
+    let prompt = game
+        .current_question()
+        .map_or("", |question| question.kind.prompt());
+    let mut components = answer_components(game.current_choices(), prompt);
+
+    if game.has_hint_left() {
+        components.push(CreateActionRow::Buttons(vec![
+            CreateButton::new(HINT_ID)
+                .label(format!("Hint (-{HINT_PENALTY} pts)"))
+                .style(ButtonStyle::Secondary),
+        ]));
+    }
+
+    components
+}
 
-            let id = &interaction.data.custom_id;
+fn reveal_content(game: &Game, index: usize, question: &Question) -> String {
+    let correct_answers = game.correct_answers();
 
-            if quote.author_id == id.parse().ok() {
-                responses.push((interaction.user.mention(), dt));
+    let scores_msg = match correct_answers.split_first() {
+        None => "No one found".to_string(),
+        Some((fastest, others)) => {
+            let mut msg = format!(
+                ":confetti_ball: Fastest was {} in {}",
+                fastest.user_id.mention(),
+                format_response_time(fastest.response_time)
+            );
+
+            for answer in others.iter().take(9) {
+                msg.push_str(&format!(
+                    "\n{}: {}",
+                    answer.user_id.mention(),
+                    format_response_time(answer.response_time)
+                ));
             }
 
-            interaction
-                .create_response(&ctx, CreateInteractionResponse::Acknowledge)
-                .await?;
+            msg
         }
+    };
+
+    let close_msg = game
+        .close_answers()
+        .iter()
+        .take(10)
+        .map(|answer| {
+            format!(
+                ":dart: {} was close with {} (+{} pts)",
+                answer.user_id.mention(),
+                choice_label(game, answer.guess),
+                answer.points
+            )
+        })
+        .join("\n");
+
+    let guesses_msg = game
+        .wrong_guesses()
+        .into_iter()
+        .map(|(guessed_user_id, count)| {
+            format!(
+                "{} thought it was {}",
+                match count {
+                    1 => "1 person".to_string(),
+                    n => format!("{n} people"),
+                },
+                choice_label(game, guessed_user_id)
+            )
+        })
+        .join("\n");
+
+    // The results are cut first, the question fits in what they leave.
+    let results = render::truncate_lines(
+        &format!(
+            r#"---
+
+Answer was: {}
+
+---
+
+{scores_msg}
+{close_msg}
+
+{guesses_msg}"#,
+            choice_label(game, question.answer)
+        ),
+        render::MAX_RESULTS_LENGTH,
+    );
+    let question = question_content(
+        index,
+        question,
+        game.shown_hints(),
+        render::MAX_MESSAGE_LENGTH - results.chars().count(),
+    );
 
-        let scores_msg = match responses.len() {
-            0 => "No one found".to_string(),
-            _ => {
-                let (username, delta) = responses.first().unwrap();
-                let mut msg = format!(
-                    ":confetti_ball: Fastest was {} in {}.{}s",
-                    username,
-                    delta.num_seconds(),
-                    delta.num_milliseconds() / 100
+    format!("{question}{results}")
+}
+
+fn format_response_time(response_time: TimeDelta) -> String {
+    format!("{:.1}s", response_time.num_milliseconds() as f64 / 1000.0)
+}
+
+/// Buttons for up to [`MAX_ANSWER_BUTTONS`] possible responses, a select menu beyond.
+fn answer_components(choices: &[Choice], prompt: &str) -> Vec<CreateActionRow> {
+    if choices.len() > MAX_ANSWER_BUTTONS {
+        let options = choices
+            .iter()
+            .map(|choice| {
+                let option = CreateSelectMenuOption::new(
+                    render::truncate(&choice.label, MAX_SELECT_LABEL_LENGTH),
+                    choice.id.to_string(),
                 );
 
-                for (username, delta) in responses.iter().skip(1).take(9) {
-                    let line = format!(
-                        "\n{}: {}.{}s",
-                        username,
-                        delta.num_seconds(),
-                        delta.num_milliseconds() / 100
-                    );
-                    msg.push_str(&line);
+                match choice_emoji(choice) {
+                    Some(emoji) => option.emoji(emoji),
+                    None => option,
                 }
+            })
+            .collect();
+
+        return vec![CreateActionRow::SelectMenu(
+            CreateSelectMenu::new(ANSWER_SELECT_ID, CreateSelectMenuKind::String { options })
+                .placeholder(prompt),
+        )];
+    }
+
+    choices
+        .chunks(5)
+        .map(|row| CreateActionRow::Buttons(row.iter().map(answer_button).collect()))
+        .collect()
+}
+
+fn answer_button(choice: &Choice) -> CreateButton {
+    let button = CreateButton::new(choice.id.to_string())
+        .label(render::truncate(&choice.label, MAX_BUTTON_LABEL_LENGTH));
+
+    match choice_emoji(choice) {
+        Some(emoji) => button.emoji(emoji),
+        None => button,
+    }
+}
+
+fn choice_emoji(choice: &Choice) -> Option<ReactionType> {
+    choice
+        .emoji
+        .as_ref()
+        .and_then(|emoji| ReactionType::try_from(emoji.as_str()).ok())
+}
 
-                msg
+/// Id of the choice picked with an answer button or in the answer select menu.
+fn guess(data: &ComponentInteractionData) -> Option<i64> {
+    match &data.kind {
+        ComponentInteractionDataKind::StringSelect { values } => values.first()?.parse().ok(),
+        _ => data.custom_id.parse().ok(),
+    }
+}
+
+fn choice_label(game: &Game, id: Option<i64>) -> &str {
+    game.choice_label(id).unwrap_or("???")
+}
+
+/// Whether the parameters of the game are asked for in a modal.
+fn wants_modal(command_interaction: &CommandInteraction) -> bool {
+    command_interaction.data.options.iter().any(|option| {
+        option.name == "modal" && option.value == CommandDataOptionValue::Boolean(true)
+    })
+}
+
+/// Reads the slash command options over the defaults of the guild.
+fn options_from_command(
+    command_interaction: &CommandInteraction,
+    config: GuildConfig,
+) -> (GameOptions, Vec<GameOptionError>) {
+    let mut options = GameOptions::from_config(config);
+    let mut errors = vec![];
+
+    for option in &command_interaction.data.options {
+        let result = match (option.name.as_str(), &option.value) {
+            ("questions", CommandDataOptionValue::Integer(value)) => {
+                check_range("questions", *value, QUESTIONS_RANGE)
+                    .map(|value| options.n_questions = value)
+            }
+            ("about", CommandDataOptionValue::String(value)) => {
+                options.question_kinds = match value.as_str() {
+                    "channel" => vec![QuestionKind::Channel],
+                    "year" => vec![QuestionKind::Year],
+                    "month" => vec![QuestionKind::Month],
+                    "real_or_fake" => vec![QuestionKind::RealOrFake],
+                    "finish_sentence" => vec![QuestionKind::FinishSentence],
+                    "mixed" => QuestionKind::ALL.to_vec(),
+                    _ => vec![QuestionKind::Author],
+                };
+                Ok(())
+            }
+            ("min_length", CommandDataOptionValue::Integer(value)) => {
+                check_range("min_length", *value, MINIMUM_QUOTE_LENGTH_RANGE)
+                    .map(|value| options.minimum_quote_length = value)
+            }
+            ("choices", CommandDataOptionValue::Integer(value)) => {
+                check_range("choices", *value, CHOICES_RANGE).map(|value| options.n_choices = value)
+            }
+            ("choices_per_question", CommandDataOptionValue::Integer(value)) => {
+                check_range("choices_per_question", *value, CHOICES_PER_QUESTION_RANGE)
+                    .map(|value| options.choices_per_question = Some(value))
+            }
+            ("distractors", CommandDataOptionValue::String(value)) => {
+                options.distractors = match value.as_str() {
+                    "same_channel" => DistractorStrategy::SameChannel,
+                    "conversation" => DistractorStrategy::Conversation,
+                    _ => DistractorStrategy::Random,
+                };
+                Ok(())
+            }
+            ("channel", CommandDataOptionValue::Channel(channel_id)) => {
+                options.channel_id = Some(*channel_id);
+                Ok(())
+            }
+            ("from", CommandDataOptionValue::String(value)) => {
+                parse_date("from", value).map(|date| options.from = Some(date))
             }
+            ("to", CommandDataOptionValue::String(value)) => {
+                parse_date("to", value).map(|date| options.to = Some(date))
+            }
+            ("timeout", CommandDataOptionValue::Integer(value)) => {
+                check_range("timeout", *value, ANSWER_TIMEOUT_SECS_RANGE)
+                    .map(|secs| options.answer_timeout = Duration::from_secs(secs.into()))
+            }
+            ("hints", CommandDataOptionValue::Boolean(value)) => {
+                options.hints = *value;
+                Ok(())
+            }
+            ("hint_after", CommandDataOptionValue::Integer(value)) => {
+                check_range("hint_after", *value, HINT_AFTER_SECS_RANGE)
+                    .map(|secs| options.hint_after = Some(Duration::from_secs(secs.into())))
+            }
+            ("include_former_members", CommandDataOptionValue::Boolean(value)) => {
+                options.include_former_members = *value;
+                Ok(())
+            }
+            ("missing_emoji", CommandDataOptionValue::String(value)) => {
+                options.missing_emoji = match value.as_str() {
+                    "exclude" => MissingEmoji::Exclude,
+                    _ => MissingEmoji::Fallback,
+                };
+                Ok(())
+            }
+            ("lobby", CommandDataOptionValue::Boolean(value)) => {
+                options.lobby = *value;
+                Ok(())
+            }
+            ("lobby_countdown", CommandDataOptionValue::Integer(value)) => {
+                check_range("lobby_countdown", *value, LOBBY_COUNTDOWN_SECS_RANGE)
+                    .map(|secs| options.lobby_countdown = Duration::from_secs(secs.into()))
+            }
+            _ => Ok(()),
         };
 
-        let quote_author_representation = game
-            .users()
-            .iter()
-            .find(|u| quote.author_id == Some(u.id))
-            .map_or("???", |u| &*u.name);
-
-        message
-            .edit(
-                &ctx,
-                EditMessage::new()
-                    .content(format!(
-                        r#"
-Question {}: Who said this ?
-> {}
----
-
-Answer was: {}
+        if let Err(error) = result {
+            errors.push(error);
+        }
+    }
 
----
+    (options, errors)
+}
 
-{scores_msg}"#,
-                        i + 1,
-                        quote.content,
-                        quote_author_representation
-                    ))
-                    .components(vec![]),
+/// Lets the numeric options be edited in a modal, prefilled with the slash command options.
+async fn options_from_modal(
+    ctx: &Context,
+    command_interaction: &CommandInteraction,
+    options: &mut GameOptions,
+    errors: &mut Vec<GameOptionError>,
+) -> Result<QuickModalResponse, CommandError> {
+    let modal = CreateQuickModal::new("Game parameters")
+        .timeout(Duration::from_secs(60))
+        .field(
+            CreateInputText::new(InputTextStyle::Short, "Number of questions", "questions")
+                .value(options.n_questions.to_string()),
+        )
+        .field(
+            CreateInputText::new(InputTextStyle::Short, "Minimum quote length", "min_length")
+                .value(options.minimum_quote_length.to_string()),
+        )
+        .field(
+            CreateInputText::new(
+                InputTextStyle::Short,
+                "Number of possible responses",
+                "choices",
+            )
+            .value(options.n_choices.to_string()),
+        )
+        .field(
+            CreateInputText::new(InputTextStyle::Short, "Seconds to answer", "timeout")
+                .value(options.answer_timeout.as_secs().to_string()),
+        )
+        .field(
+            CreateInputText::new(
+                InputTextStyle::Short,
+                "Include former members (yes/no)",
+                "include_former_members",
             )
-            .await?;
+            .value(
+                if options.include_former_members {
+                    "yes"
+                } else {
+                    "no"
+                }
+                .to_owned(),
+            ),
+        );
 
-        sleep(Duration::from_secs(5)).await;
-    }
+    let response = command_interaction
+        .quick_modal(ctx, modal)
+        .await?
+        .ok_or(CommandError::ModalDismissed)?;
+    let inputs = &response.inputs;
+
+    let results = [
+        parse_number("questions", &inputs[0], QUESTIONS_RANGE)
+            .map(|value| options.n_questions = value),
+        parse_number("min_length", &inputs[1], MINIMUM_QUOTE_LENGTH_RANGE)
+            .map(|value| options.minimum_quote_length = value),
+        parse_number("choices", &inputs[2], CHOICES_RANGE).map(|value| options.n_choices = value),
+        parse_number("timeout", &inputs[3], ANSWER_TIMEOUT_SECS_RANGE)
+            .map(|secs| options.answer_timeout = Duration::from_secs(secs.into())),
+        parse_yes_no("include_former_members", &inputs[4])
+            .map(|value| options.include_former_members = value),
+    ];
+
+    errors.extend(results.into_iter().filter_map(Result::err));
+
+    Ok(response)
+}
+
+/// Answers the command, or the modal when one was shown, whose response was deferred.
+async fn respond(
+    ctx: &Context,
+    command_interaction: &CommandInteraction,
+    modal_interaction: Option<&ModalInteraction>,
+    message: EditInteractionResponse,
+) -> serenity::Result<()> {
+    match modal_interaction {
+        Some(modal_interaction) => modal_interaction.edit_response(ctx, message).await?,
+        None => command_interaction.edit_response(ctx, message).await?,
+    };
 
     Ok(())
 }
 
+/// Replaces the deferred response of the command, or of the modal when one was shown, with a
+/// message only the user can see. A deferred response cannot be made ephemeral, it is deleted.
+async fn respond_ephemeral(
+    ctx: &Context,
+    command_interaction: &CommandInteraction,
+    modal_interaction: Option<&ModalInteraction>,
+    content: String,
+) -> serenity::Result<()> {
+    let followup = CreateInteractionResponseFollowup::new()
+        .content(content)
+        .ephemeral(true);
+
+    match modal_interaction {
+        Some(modal_interaction) => {
+            modal_interaction.delete_response(ctx).await?;
+            modal_interaction.create_followup(ctx, followup).await?
+        }
+        None => {
+            command_interaction.delete_response(ctx).await?;
+            command_interaction.create_followup(ctx, followup).await?
+        }
+    };
+
+    Ok(())
+}
+
+fn ephemeral_message(content: impl Into<String>) -> CreateInteractionResponse {
+    CreateInteractionResponse::Message(
+        CreateInteractionResponseMessage::new()
+            .content(content)
+            .ephemeral(true),
+    )
+}
+
+fn summary_embed(scoreboard: &Scoreboard, stopped: bool) -> CreateEmbed {
+    let embed = CreateEmbed::new().title(match stopped {
+        true => ":octagonal_sign: Game stopped",
+        false => ":checkered_flag: Game over",
+    });
+
+    if scoreboard.is_empty() {
+        return embed.description("Nobody played :(");
+    }
+
+    let ranking = scoreboard
+        .ranking()
+        .iter()
+        .enumerate()
+        .map(|(i, (user_id, stats))| {
+            let fastest = stats.fastest_answer.map_or("-".to_string(), |fastest| {
+                format!("{:.1}s", fastest.num_milliseconds() as f64 / 1000.0)
+            });
+
+            format!(
+                "{}. {} **{} pts** · {:.0}% accuracy · streak {} · fastest {}",
+                i + 1,
+                user_id.mention(),
+                stats.points,
+                stats.accuracy() * 100.0,
+                stats.longest_streak,
+                fastest
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    embed.description(ranking)
+}
+
 pub fn register() -> CreateCommand {
-    CreateCommand::new("whosaid").description("Start a whosaid game")
+    CreateCommand::new("whosaid")
+        .description("Start a whosaid game")
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "questions",
+                "Number of questions",
+            )
+            .min_int_value(*QUESTIONS_RANGE.start() as u64)
+            .max_int_value(*QUESTIONS_RANGE.end() as u64),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "about",
+                "What the questions ask about the quotes",
+            )
+            .add_string_choice("Who said it", "author")
+            .add_string_choice("Which channel it was said in", "channel")
+            .add_string_choice("Which year it was said in", "year")
+            .add_string_choice("Which month it was said in", "month")
+            .add_string_choice(
+                "Whether it is real or a fake, and who said it",
+                "real_or_fake",
+            )
+            .add_string_choice("How it ends", "finish_sentence")
+            .add_string_choice("A bit of everything", "mixed"),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "min_length",
+                "Minimum quote length",
+            )
+            .min_int_value(*MINIMUM_QUOTE_LENGTH_RANGE.start() as u64)
+            .max_int_value(*MINIMUM_QUOTE_LENGTH_RANGE.end() as u64),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "choices",
+                "Number of possible responses",
+            )
+            .min_int_value(*CHOICES_RANGE.start() as u64)
+            .max_int_value(*CHOICES_RANGE.end() as u64),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "choices_per_question",
+                "Only show this many possible responses on each question",
+            )
+            .min_int_value(*CHOICES_PER_QUESTION_RANGE.start() as u64)
+            .max_int_value(*CHOICES_PER_QUESTION_RANGE.end() as u64),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "distractors",
+                "How the wrong possible responses are picked with choices_per_question",
+            )
+            .add_string_choice("At random", "random")
+            .add_string_choice(
+                "Similar activity in the channel of the quote",
+                "same_channel",
+            )
+            .add_string_choice("People who talk with the author", "conversation"),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Channel,
+                "channel",
+                "Only quote messages from this channel and its threads",
+            )
+            .channel_types(vec![
+                ChannelType::Text,
+                ChannelType::News,
+                ChannelType::Forum,
+                ChannelType::PublicThread,
+                ChannelType::PrivateThread,
+                ChannelType::NewsThread,
+            ]),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "from",
+            "Only quote messages sent on or after this day (YYYY-MM-DD)",
+        ))
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "to",
+            "Only quote messages sent on or before this day (YYYY-MM-DD)",
+        ))
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::Integer, "timeout", "Seconds to answer")
+                .min_int_value(*ANSWER_TIMEOUT_SECS_RANGE.start() as u64)
+                .max_int_value(*ANSWER_TIMEOUT_SECS_RANGE.end() as u64),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::Boolean,
+            "hints",
+            "Let players ask for hints, each hint lowers the points of the next answers",
+        ))
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "hint_after",
+                "Show a hint every this many seconds",
+            )
+            .min_int_value(*HINT_AFTER_SECS_RANGE.start() as u64)
+            .max_int_value(*HINT_AFTER_SECS_RANGE.end() as u64),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::Boolean,
+            "include_former_members",
+            "Include members who left the server as possible responses",
+        ))
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "missing_emoji",
+                "What to do with possible responses who did not set an emoji with /emoji",
+            )
+            .add_string_choice("Give them a generated emoji", "fallback")
+            .add_string_choice("Leave them out of the game", "exclude"),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::Boolean,
+            "lobby",
+            "Let players join before the game starts, only they can answer",
+        ))
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "lobby_countdown",
+                "Seconds before a game with a lobby starts",
+            )
+            .min_int_value(*LOBBY_COUNTDOWN_SECS_RANGE.start() as u64)
+            .max_int_value(*LOBBY_COUNTDOWN_SECS_RANGE.end() as u64),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::Boolean,
+            "modal",
+            "Set the game parameters in a form",
+        ))
 }
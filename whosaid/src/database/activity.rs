@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+use entity::prelude::*;
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, EntityTrait, FromQueryResult, QueryFilter, QuerySelect, Statement,
+};
+
+use crate::database::Database;
+use crate::database::error::DatabaseError;
+
+/// Conversations are looked for in the latest messages of a channel only, the scan runs for each
+/// question.
+const CONVERSATION_WINDOW: i64 = 5000;
+
+#[derive(FromQueryResult)]
+struct UserScore {
+    user_id: i64,
+    score: i64,
+}
+
+impl Database {
+    /// Number of messages each of the given users sent in a channel, users who never talked there
+    /// are left out.
+    pub async fn get_channel_activity(
+        &self,
+        channel_id: i64,
+        user_ids: &[i64],
+    ) -> Result<HashMap<i64, i64>, DatabaseError> {
+        let scores = Message::find()
+            .select_only()
+            .column_as(entity::message::Column::AuthorId, "user_id")
+            .column_as(entity::message::Column::Id.count(), "score")
+            .filter(entity::message::Column::ChannelId.eq(channel_id))
+            .filter(entity::message::Column::AuthorId.is_in(user_ids.iter().copied()))
+            .filter(entity::message::Column::DeletedAt.is_null())
+            .group_by(entity::message::Column::AuthorId)
+            .into_model::<UserScore>()
+            .all(&self.db)
+            .await?;
+
+        Ok(scores
+            .into_iter()
+            .map(|score| (score.user_id, score.score))
+            .collect())
+    }
+
+    /// How many times each user sent a message right after one of `author_id` among the latest
+    /// messages of a channel.
+    pub async fn get_conversation_partners(
+        &self,
+        channel_id: i64,
+        author_id: i64,
+    ) -> Result<HashMap<i64, i64>, DatabaseError> {
+        let statement = Statement::from_sql_and_values(
+            self.db.get_database_backend(),
+            r#"SELECT "author_id" AS "user_id", COUNT(*) AS "score"
+            FROM (
+                SELECT "author_id", LAG("author_id") OVER (ORDER BY "id") AS "previous_author_id"
+                FROM (
+                    SELECT "id", "author_id"
+                    FROM "message"
+                    WHERE "channel_id" = $1 AND "deleted_at" IS NULL
+                    ORDER BY "id" DESC
+                    LIMIT $3
+                ) AS "recent"
+            ) AS "neighbors"
+            WHERE "previous_author_id" = $2 AND "author_id" <> $2
+            GROUP BY "author_id""#,
+            [
+                channel_id.into(),
+                author_id.into(),
+                CONVERSATION_WINDOW.into(),
+            ],
+        );
+
+        let scores = UserScore::find_by_statement(statement)
+            .all(&self.db)
+            .await?;
+
+        Ok(scores
+            .into_iter()
+            .map(|score| (score.user_id, score.score))
+            .collect())
+    }
+}
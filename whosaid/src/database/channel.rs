@@ -1,13 +1,16 @@
+use std::collections::HashMap;
+
 use sea_orm::QueryOrder;
 use sea_orm::ActiveValue::Set;
 use sea_orm::{
-    DbErr, EntityTrait, QueryFilter,
+    ColumnTrait, DbErr, EntityTrait, QueryFilter, QuerySelect,
 };
-use sea_query::Expr;
-use serenity::all::{ChannelId, GuildChannel as DiscordChannel};
+use sea_query::{Expr, Query};
+use serenity::all::{ChannelId, GuildChannel as DiscordChannel, GuildId};
 
 use crate::database::error::DatabaseError;
 use crate::database::Database;
+use crate::database::messages::QuoteScope;
 use entity::prelude::*;
 use entity::{channel, message};
 
@@ -34,15 +37,20 @@ impl Database {
         &self,
         discord_channels: &[DiscordChannel],
     ) -> Result<(), DatabaseError> {
+        if discord_channels.is_empty() {
+            return Ok(());
+        }
+
         let new_channels: Vec<channel::ActiveModel> = discord_channels
             .iter()
             .map(Self::map_channel_to_active_model)
             .collect();
 
+        // Channels can be marked NSFW after they were saved.
         match Channel::insert_many(new_channels)
             .on_conflict(
                 sea_query::OnConflict::column(channel::Column::Id)
-                    .do_nothing()
+                    .update_column(channel::Column::Nsfw)
                     .to_owned(),
             )
             .exec(&self.db)
@@ -64,6 +72,67 @@ impl Database {
             .ok_or(DatabaseError::NotFound)
     }
 
+    /// Channels of a guild, without their threads, quotes can come from: the ones the scrape rules
+    /// save, NSFW channels are not by default, and `scope` allows. Forums only hold threads, they
+    /// have no message of their own and are left out.
+    pub async fn get_quotable_channels(
+        &self,
+        guild_id: GuildId,
+        scope: &QuoteScope,
+    ) -> Result<Vec<channel::Model>, DatabaseError> {
+        let rules: HashMap<i64, bool> = GuildScrapeChannel::find()
+            .select_only()
+            .column(entity::guild_scrape_channel::Column::ChannelId)
+            .column(entity::guild_scrape_channel::Column::Scraped)
+            .filter(entity::guild_scrape_channel::Column::GuildId.eq(i64::from(guild_id)))
+            .into_tuple()
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .collect();
+
+        let channels = Channel::find()
+            .filter(channel::Column::GuildId.eq(i64::from(guild_id)))
+            .filter(channel::Column::ParentId.is_null())
+            .filter(Expr::exists(
+                Query::select()
+                    .expr(Expr::val(1))
+                    .from(Message)
+                    .and_where(
+                        Expr::col((Message, message::Column::ChannelId))
+                            .equals((Channel, channel::Column::Id)),
+                    )
+                    .to_owned(),
+            ))
+            .all(&self.db)
+            .await?;
+
+        Ok(channels
+            .into_iter()
+            .filter(|channel| rules.get(&channel.id).copied().unwrap_or(!channel.nsfw))
+            .filter(|channel| scope.allows_channel(ChannelId::new(channel.id as u64)))
+            .collect())
+    }
+
+    /// Saved channels and threads of a guild.
+    pub async fn get_guild_channel_ids(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Vec<ChannelId>, DatabaseError> {
+        let channel_ids: Vec<i64> = Channel::find()
+            .select_only()
+            .column(channel::Column::Id)
+            .filter(channel::Column::GuildId.eq(i64::from(guild_id)))
+            .into_tuple()
+            .all(&self.db)
+            .await?;
+
+        Ok(channel_ids
+            .into_iter()
+            .map(|channel_id| ChannelId::new(channel_id as u64))
+            .collect())
+    }
+
     pub async fn set_channel_backfilled(&self, channel_id: ChannelId) -> Result<(), DatabaseError> {
         let updated = channel::ActiveModel {
             id: Set(i64::from(channel_id)),
@@ -105,6 +174,14 @@ impl Database {
             guild_id: Set(i64::from(discord_channel.guild_id)),
             last_message_id: Set(discord_channel.last_message_id.map(i64::from)),
             backfill_done: Set(false),
+            // Only threads keep a reference to their channel, the parent of other channels is
+            // their category.
+            parent_id: Set(discord_channel
+                .thread_metadata
+                .as_ref()
+                .and(discord_channel.parent_id)
+                .map(i64::from)),
+            nsfw: Set(discord_channel.nsfw),
         }
     }
 }
@@ -0,0 +1,93 @@
+use std::collections::HashSet;
+
+use chrono::Local;
+use sea_orm::{ActiveValue, ColumnTrait, DbErr, EntityTrait, QueryFilter, QuerySelect};
+use sea_query::Query;
+use serenity::all::{GuildId, UserId};
+
+use entity::prelude::*;
+
+use crate::database::Database;
+use crate::database::error::DatabaseError;
+
+impl Database {
+    pub async fn set_user_opted_out(
+        &self,
+        user_id: UserId,
+        guild_id: GuildId,
+        opted_out: bool,
+    ) -> Result<(), DatabaseError> {
+        let user_consent = entity::user_consent::ActiveModel {
+            user_id: ActiveValue::Set(user_id.into()),
+            guild_id: ActiveValue::Set(guild_id.into()),
+            opted_out: ActiveValue::Set(opted_out),
+            updated_at: ActiveValue::Set(Local::now().fixed_offset()),
+        };
+
+        match UserConsent::insert(user_consent)
+            .on_conflict(
+                sea_query::OnConflict::columns([
+                    entity::user_consent::Column::UserId,
+                    entity::user_consent::Column::GuildId,
+                ])
+                .update_columns([
+                    entity::user_consent::Column::OptedOut,
+                    entity::user_consent::Column::UpdatedAt,
+                ])
+                .to_owned(),
+            )
+            .exec(&self.db)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(DbErr::RecordNotInserted) => Ok(()),
+            Err(err) => Err(DatabaseError::from(err)),
+        }
+    }
+
+    pub async fn get_opted_out_users(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<HashSet<UserId>, DatabaseError> {
+        let user_ids: Vec<i64> = UserConsent::find()
+            .select_only()
+            .column(entity::user_consent::Column::UserId)
+            .filter(entity::user_consent::Column::GuildId.eq(i64::from(guild_id)))
+            .filter(entity::user_consent::Column::OptedOut.eq(true))
+            .into_tuple()
+            .all(&self.db)
+            .await?;
+
+        Ok(user_ids
+            .into_iter()
+            .map(|user_id| UserId::new(user_id as u64))
+            .collect())
+    }
+
+    /// Removes everything stored about a user in a guild: their messages and their emoji.
+    pub async fn forget_user(
+        &self,
+        user_id: UserId,
+        guild_id: GuildId,
+    ) -> Result<(), DatabaseError> {
+        let guild_channels = Query::select()
+            .column(entity::channel::Column::Id)
+            .from(Channel)
+            .and_where(entity::channel::Column::GuildId.eq(i64::from(guild_id)))
+            .to_owned();
+
+        Message::delete_many()
+            .filter(entity::message::Column::AuthorId.eq(i64::from(user_id)))
+            .filter(entity::message::Column::ChannelId.in_subquery(guild_channels))
+            .exec(&self.db)
+            .await?;
+
+        UserEmoji::delete_many()
+            .filter(entity::user_emoji::Column::UserId.eq(i64::from(user_id)))
+            .filter(entity::user_emoji::Column::GuildId.eq(i64::from(guild_id)))
+            .exec(&self.db)
+            .await?;
+
+        Ok(())
+    }
+}
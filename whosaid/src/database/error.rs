@@ -1,4 +1,5 @@
 use sea_orm::DbErr;
+use serenity::all::UserId;
 
 #[derive(Debug, thiserror::Error)]
 pub enum DatabaseError {
@@ -7,4 +8,7 @@ pub enum DatabaseError {
 
     #[error("Not found")]
     NotFound,
+
+    #[error("Emoji already claimed by {0}")]
+    EmojiClaimed(UserId),
 }
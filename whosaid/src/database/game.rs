@@ -0,0 +1,158 @@
+use chrono::{DateTime, FixedOffset, Local};
+use sea_orm::{
+    ActiveValue, ColumnTrait, DbErr, EntityTrait, FromQueryResult, QueryFilter, QueryOrder,
+    QuerySelect, RelationTrait,
+};
+use sea_query::{Alias, Expr, JoinType};
+use serenity::all::{ChannelId, GuildId, UserId};
+
+use entity::prelude::*;
+
+use crate::database::Database;
+use crate::database::error::DatabaseError;
+
+pub struct NewGameAnswer {
+    pub(crate) user_id: UserId,
+    pub(crate) guessed_user_id: Option<i64>,
+    pub(crate) correct: bool,
+    pub(crate) response_time_ms: i64,
+    pub(crate) points: i64,
+}
+
+#[derive(FromQueryResult)]
+pub struct LeaderboardEntry {
+    pub(crate) user_id: i64,
+    pub(crate) points: i64,
+    pub(crate) correct_answers: i64,
+}
+
+impl Database {
+    pub async fn create_game(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        started_by: UserId,
+    ) -> Result<i64, DatabaseError> {
+        let new_game = entity::game::ActiveModel {
+            guild_id: ActiveValue::Set(guild_id.into()),
+            channel_id: ActiveValue::Set(channel_id.into()),
+            started_by: ActiveValue::Set(started_by.into()),
+            started_at: ActiveValue::Set(Local::now().fixed_offset()),
+            ended_at: ActiveValue::Set(None),
+            ..Default::default()
+        };
+
+        Ok(Game::insert(new_game).exec(&self.db).await?.last_insert_id)
+    }
+
+    pub async fn end_game(&self, game_id: i64) -> Result<(), DatabaseError> {
+        let updated = entity::game::ActiveModel {
+            id: ActiveValue::Set(game_id),
+            ended_at: ActiveValue::Set(Some(Local::now().fixed_offset())),
+            ..Default::default()
+        };
+
+        Game::update(updated).exec(&self.db).await?;
+
+        Ok(())
+    }
+
+    /// `message_id` is `None` for quotes which were never said.
+    pub async fn save_game_question(
+        &self,
+        game_id: i64,
+        position: usize,
+        message_id: Option<i64>,
+        author_id: Option<i64>,
+        kind: &str,
+        asked_at: DateTime<FixedOffset>,
+    ) -> Result<i64, DatabaseError> {
+        let new_question = entity::game_question::ActiveModel {
+            game_id: ActiveValue::Set(game_id),
+            position: ActiveValue::Set(position as i32),
+            message_id: ActiveValue::Set(message_id),
+            author_id: ActiveValue::Set(author_id),
+            asked_at: ActiveValue::Set(asked_at),
+            kind: ActiveValue::Set(kind.to_string()),
+            ..Default::default()
+        };
+
+        Ok(GameQuestion::insert(new_question)
+            .exec(&self.db)
+            .await?
+            .last_insert_id)
+    }
+
+    pub async fn save_game_answers(
+        &self,
+        game_question_id: i64,
+        answers: &[NewGameAnswer],
+    ) -> Result<(), DatabaseError> {
+        if answers.is_empty() {
+            return Ok(());
+        }
+
+        let new_answers: Vec<entity::game_answer::ActiveModel> = answers
+            .iter()
+            .map(|answer| entity::game_answer::ActiveModel {
+                game_question_id: ActiveValue::Set(game_question_id),
+                user_id: ActiveValue::Set(answer.user_id.into()),
+                guessed_user_id: ActiveValue::Set(answer.guessed_user_id),
+                correct: ActiveValue::Set(answer.correct),
+                response_time_ms: ActiveValue::Set(answer.response_time_ms),
+                points: ActiveValue::Set(answer.points),
+                ..Default::default()
+            })
+            .collect();
+
+        match GameAnswer::insert_many(new_answers).exec(&self.db).await {
+            Ok(_) => Ok(()),
+            Err(DbErr::RecordNotInserted) => Ok(()),
+            Err(err) => Err(DatabaseError::from(err)),
+        }
+    }
+
+    /// Ranks the players of a guild by their total points, optionally only counting the games
+    /// started after `since`.
+    pub async fn get_leaderboard(
+        &self,
+        guild_id: GuildId,
+        since: Option<DateTime<FixedOffset>>,
+        limit: u64,
+    ) -> Result<Vec<LeaderboardEntry>, DatabaseError> {
+        let mut select = GameAnswer::find()
+            .select_only()
+            .column(entity::game_answer::Column::UserId)
+            .column_as(
+                entity::game_answer::Column::Points
+                    .sum()
+                    .cast_as(Alias::new("BIGINT")),
+                "points",
+            )
+            .column_as(
+                Expr::cust("COUNT(*) FILTER (WHERE \"game_answer\".\"correct\")"),
+                "correct_answers",
+            )
+            .join(
+                JoinType::InnerJoin,
+                entity::game_answer::Relation::GameQuestion.def(),
+            )
+            .join(
+                JoinType::InnerJoin,
+                entity::game_question::Relation::Game.def(),
+            )
+            .filter(entity::game::Column::GuildId.eq(i64::from(guild_id)));
+
+        if let Some(since) = since {
+            select = select.filter(entity::game::Column::StartedAt.gte(since));
+        }
+
+        Ok(select
+            .group_by(entity::game_answer::Column::UserId)
+            .order_by_desc(entity::game_answer::Column::Points.sum())
+            .limit(limit)
+            .into_model::<LeaderboardEntry>()
+            .all(&self.db)
+            .await?)
+    }
+}
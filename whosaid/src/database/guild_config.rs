@@ -0,0 +1,190 @@
+use std::time::Duration;
+
+use sea_orm::{ActiveValue, ColumnTrait, EntityTrait, QueryFilter, QuerySelect};
+use serenity::all::{ChannelId, GuildId};
+
+use entity::prelude::{GuildConfigChannel, GuildScrapeChannel};
+
+use crate::database::Database;
+use crate::database::error::DatabaseError;
+use crate::game::options::GameOptions;
+use crate::scrapper::SCRAPE_INTERVAL;
+
+/// Defaults of the games of a guild, the options of `/whosaid` override them.
+pub struct GuildConfig {
+    pub n_questions: u32,
+    pub minimum_quote_length: u32,
+    pub n_choices: u32,
+    pub answer_timeout: Duration,
+    /// Time between the start announcement and the first question, for games without a lobby.
+    pub start_delay: Duration,
+    /// Time the answer of a question stays on screen before the next question.
+    pub reveal_duration: Duration,
+    /// Time between two catch-ups of the channels, which recover the messages of the gateway
+    /// events the bot missed.
+    pub scrape_interval: Duration,
+    /// Only quote messages from these channels and their threads, any channel when empty.
+    pub allowed_channels: Vec<ChannelId>,
+    /// Never quote messages from these channels and their threads.
+    pub denied_channels: Vec<ChannelId>,
+    /// Always scrape these channels and their threads, even NSFW ones.
+    pub scraped_channels: Vec<ChannelId>,
+    /// Never scrape these channels and their threads.
+    pub ignored_channels: Vec<ChannelId>,
+}
+
+impl Default for GuildConfig {
+    fn default() -> Self {
+        let options = GameOptions::default();
+
+        Self {
+            n_questions: options.n_questions,
+            minimum_quote_length: options.minimum_quote_length,
+            n_choices: options.n_choices,
+            answer_timeout: options.answer_timeout,
+            start_delay: options.start_delay,
+            reveal_duration: options.reveal_duration,
+            scrape_interval: SCRAPE_INTERVAL,
+            allowed_channels: options.allowed_channels,
+            denied_channels: options.denied_channels,
+            scraped_channels: vec![],
+            ignored_channels: vec![],
+        }
+    }
+}
+
+impl Database {
+    pub async fn get_guild_config(&self, guild_id: GuildId) -> Result<GuildConfig, DatabaseError> {
+        let settings = entity::guild_config::Entity::find_by_id(i64::from(guild_id))
+            .one(&self.db)
+            .await?;
+
+        // Each channel is listed with whether it is allowed, or scraped.
+        let quoted_channels: Vec<(i64, bool)> = GuildConfigChannel::find()
+            .select_only()
+            .column(entity::guild_config_channel::Column::ChannelId)
+            .column(entity::guild_config_channel::Column::Allowed)
+            .filter(entity::guild_config_channel::Column::GuildId.eq(i64::from(guild_id)))
+            .into_tuple()
+            .all(&self.db)
+            .await?;
+        let scraped_channels: Vec<(i64, bool)> = GuildScrapeChannel::find()
+            .select_only()
+            .column(entity::guild_scrape_channel::Column::ChannelId)
+            .column(entity::guild_scrape_channel::Column::Scraped)
+            .filter(entity::guild_scrape_channel::Column::GuildId.eq(i64::from(guild_id)))
+            .into_tuple()
+            .all(&self.db)
+            .await?;
+        let ids = |channels: &[(i64, bool)], listed: bool| {
+            channels
+                .iter()
+                .filter(|(_, flag)| *flag == listed)
+                .map(|(channel_id, _)| ChannelId::new(*channel_id as u64))
+                .collect()
+        };
+
+        let config = match settings {
+            Some(settings) => GuildConfig {
+                n_questions: settings.questions as u32,
+                minimum_quote_length: settings.minimum_quote_length as u32,
+                n_choices: settings.choices as u32,
+                answer_timeout: Duration::from_secs(settings.answer_timeout_secs as u64),
+                start_delay: Duration::from_secs(settings.start_delay_secs as u64),
+                reveal_duration: Duration::from_secs(settings.reveal_secs as u64),
+                scrape_interval: Duration::from_secs(settings.scrape_interval_secs as u64),
+                ..Default::default()
+            },
+            None => GuildConfig::default(),
+        };
+
+        Ok(GuildConfig {
+            allowed_channels: ids(&quoted_channels, true),
+            denied_channels: ids(&quoted_channels, false),
+            scraped_channels: ids(&scraped_channels, true),
+            ignored_channels: ids(&scraped_channels, false),
+            ..config
+        })
+    }
+
+    /// Saves the defaults of the games, the channels are saved one by one.
+    pub async fn save_guild_config(
+        &self,
+        guild_id: GuildId,
+        config: &GuildConfig,
+    ) -> Result<(), DatabaseError> {
+        let settings = entity::guild_config::ActiveModel {
+            guild_id: ActiveValue::Set(guild_id.into()),
+            questions: ActiveValue::Set(config.n_questions as i32),
+            minimum_quote_length: ActiveValue::Set(config.minimum_quote_length as i32),
+            choices: ActiveValue::Set(config.n_choices as i32),
+            answer_timeout_secs: ActiveValue::Set(config.answer_timeout.as_secs() as i32),
+            start_delay_secs: ActiveValue::Set(config.start_delay.as_secs() as i32),
+            reveal_secs: ActiveValue::Set(config.reveal_duration.as_secs() as i32),
+            scrape_interval_secs: ActiveValue::Set(config.scrape_interval.as_secs() as i32),
+        };
+
+        entity::guild_config::Entity::insert(settings)
+            .on_conflict(
+                sea_query::OnConflict::column(entity::guild_config::Column::GuildId)
+                    .update_columns([
+                        entity::guild_config::Column::Questions,
+                        entity::guild_config::Column::MinimumQuoteLength,
+                        entity::guild_config::Column::Choices,
+                        entity::guild_config::Column::AnswerTimeoutSecs,
+                        entity::guild_config::Column::StartDelaySecs,
+                        entity::guild_config::Column::RevealSecs,
+                        entity::guild_config::Column::ScrapeIntervalSecs,
+                    ])
+                    .to_owned(),
+            )
+            .exec(&self.db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Puts the channel on the allow-list of the guild, or on its deny-list, moving it off the
+    /// other list.
+    pub async fn save_config_channel(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        allowed: bool,
+    ) -> Result<(), DatabaseError> {
+        let channel = entity::guild_config_channel::ActiveModel {
+            guild_id: ActiveValue::Set(guild_id.into()),
+            channel_id: ActiveValue::Set(channel_id.into()),
+            allowed: ActiveValue::Set(allowed),
+        };
+
+        GuildConfigChannel::insert(channel)
+            .on_conflict(
+                sea_query::OnConflict::columns([
+                    entity::guild_config_channel::Column::GuildId,
+                    entity::guild_config_channel::Column::ChannelId,
+                ])
+                .update_column(entity::guild_config_channel::Column::Allowed)
+                .to_owned(),
+            )
+            .exec(&self.db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns whether the channel was on one of the lists of the guild.
+    pub async fn remove_config_channel(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+    ) -> Result<bool, DatabaseError> {
+        let result = GuildConfigChannel::delete_many()
+            .filter(entity::guild_config_channel::Column::GuildId.eq(i64::from(guild_id)))
+            .filter(entity::guild_config_channel::Column::ChannelId.eq(i64::from(channel_id)))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+}
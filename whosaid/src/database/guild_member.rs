@@ -0,0 +1,118 @@
+use std::collections::HashSet;
+
+use chrono::Local;
+use sea_orm::{ActiveValue, ColumnTrait, DbErr, EntityTrait, QueryFilter, QuerySelect};
+use sea_query::Expr;
+use serenity::all::{GuildId, Member, UserId};
+
+use entity::prelude::*;
+
+use crate::database::error::DatabaseError;
+use crate::database::{Database, INSERT_BATCH_SIZE};
+
+impl Database {
+    /// Saves the current members of a guild, members who come back are not former members
+    /// anymore.
+    pub async fn save_guild_members(
+        &self,
+        guild_id: GuildId,
+        discord_members: &[Member],
+    ) -> Result<(), DatabaseError> {
+        for discord_members in discord_members.chunks(INSERT_BATCH_SIZE) {
+            let new_members: Vec<entity::guild_member::ActiveModel> = discord_members
+                .iter()
+                .map(|member| entity::guild_member::ActiveModel {
+                    user_id: ActiveValue::Set(member.user.id.into()),
+                    guild_id: ActiveValue::Set(guild_id.into()),
+                    joined_at: ActiveValue::Set(
+                        member.joined_at.map(|joined_at| joined_at.fixed_offset()),
+                    ),
+                    left_at: ActiveValue::Set(None),
+                })
+                .collect();
+
+            match GuildMember::insert_many(new_members)
+                .on_conflict(
+                    sea_query::OnConflict::columns([
+                        entity::guild_member::Column::UserId,
+                        entity::guild_member::Column::GuildId,
+                    ])
+                    .update_columns([
+                        entity::guild_member::Column::JoinedAt,
+                        entity::guild_member::Column::LeftAt,
+                    ])
+                    .to_owned(),
+                )
+                .exec(&self.db)
+                .await
+            {
+                Ok(_) => {}
+                Err(DbErr::RecordNotInserted) => {}
+                Err(err) => return Err(DatabaseError::from(err)),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Marks the members of a guild missing from `current_members` as former members.
+    pub async fn set_former_guild_members(
+        &self,
+        guild_id: GuildId,
+        current_members: &[Member],
+    ) -> Result<(), DatabaseError> {
+        let current_members: HashSet<i64> = current_members
+            .iter()
+            .map(|member| i64::from(member.user.id))
+            .collect();
+
+        let stored_members: Vec<i64> = GuildMember::find()
+            .select_only()
+            .column(entity::guild_member::Column::UserId)
+            .filter(entity::guild_member::Column::GuildId.eq(i64::from(guild_id)))
+            .filter(entity::guild_member::Column::LeftAt.is_null())
+            .into_tuple()
+            .all(&self.db)
+            .await?;
+
+        let former_members: Vec<i64> = stored_members
+            .into_iter()
+            .filter(|user_id| !current_members.contains(user_id))
+            .collect();
+
+        for former_members in former_members.chunks(INSERT_BATCH_SIZE) {
+            self.set_guild_members_left(guild_id, former_members)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn set_guild_member_left(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<(), DatabaseError> {
+        self.set_guild_members_left(guild_id, &[i64::from(user_id)])
+            .await
+    }
+
+    async fn set_guild_members_left(
+        &self,
+        guild_id: GuildId,
+        user_ids: &[i64],
+    ) -> Result<(), DatabaseError> {
+        GuildMember::update_many()
+            .col_expr(
+                entity::guild_member::Column::LeftAt,
+                Expr::value(Local::now().fixed_offset()),
+            )
+            .filter(entity::guild_member::Column::GuildId.eq(i64::from(guild_id)))
+            .filter(entity::guild_member::Column::UserId.is_in(user_ids.iter().copied()))
+            .filter(entity::guild_member::Column::LeftAt.is_null())
+            .exec(&self.db)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -1,32 +1,150 @@
+use chrono::{DateTime, FixedOffset, Local};
 use itertools::Itertools;
 use sea_orm::{
-    ActiveValue, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, FromQueryResult, QueryFilter,
-    QuerySelect, QueryTrait, RelationTrait,
+    ActiveValue, ColumnTrait, Condition, ConnectionTrait, DbErr, EntityTrait, FromQueryResult,
+    QueryFilter, QueryOrder, QuerySelect, QueryTrait, RelationTrait,
 };
-use sea_query::{Expr, Func, JoinType, Order, SimpleExpr};
-use serenity::all::{GuildId, Message as DiscordMessage, MessageId};
-use std::collections::HashSet;
+use sea_query::{Expr, Func, JoinType, Order, Query, SimpleExpr};
+use serenity::all::{ChannelId, GuildId, Message as DiscordMessage, MessageId};
 
 use entity::prelude::*;
 
 use crate::database::Database;
 use crate::database::error::DatabaseError;
+use crate::database::quote_filter::QuoteFilters;
+
+/// Where and when the quotes of a game can come from.
+#[derive(Default)]
+pub struct QuoteScope {
+    /// Only quote messages from this channel and its threads.
+    pub channel_id: Option<ChannelId>,
+    pub sent_after: Option<DateTime<FixedOffset>>,
+    pub sent_before: Option<DateTime<FixedOffset>>,
+    /// Only quote messages from these channels and their threads, any channel when empty.
+    pub allowed_channel_ids: Vec<ChannelId>,
+    /// Never quote messages from these channels and their threads.
+    pub denied_channel_ids: Vec<ChannelId>,
+    /// Never quote these messages, as the ones already picked for a game.
+    pub excluded_message_ids: Vec<i64>,
+}
+
+impl QuoteScope {
+    /// Whether the allowed and denied channels let a channel without parent be quoted.
+    pub(crate) fn allows_channel(&self, channel_id: ChannelId) -> bool {
+        (self.allowed_channel_ids.is_empty() || self.allowed_channel_ids.contains(&channel_id))
+            && !self.denied_channel_ids.contains(&channel_id)
+    }
+
+    /// Condition on the `message` table, joined with its `channel`, messages in scope satisfy.
+    fn condition(&self) -> Condition {
+        let ids = |channel_ids: &[ChannelId]| -> Vec<i64> {
+            channel_ids.iter().map(|id| i64::from(*id)).collect()
+        };
+        let mut condition = Condition::all();
+
+        if let Some(channel_id) = self.channel_id {
+            condition = condition.add(
+                Condition::any()
+                    .add(entity::channel::Column::Id.eq(i64::from(channel_id)))
+                    .add(entity::channel::Column::ParentId.eq(i64::from(channel_id))),
+            );
+        }
+        if !self.allowed_channel_ids.is_empty() {
+            condition = condition.add(
+                Condition::any()
+                    .add(entity::channel::Column::Id.is_in(ids(&self.allowed_channel_ids)))
+                    .add(entity::channel::Column::ParentId.is_in(ids(&self.allowed_channel_ids))),
+            );
+        }
+        if !self.denied_channel_ids.is_empty() {
+            condition = condition
+                .add(entity::channel::Column::Id.is_not_in(ids(&self.denied_channel_ids)))
+                .add(
+                    Condition::any()
+                        .add(entity::channel::Column::ParentId.is_null())
+                        .add(
+                            entity::channel::Column::ParentId
+                                .is_not_in(ids(&self.denied_channel_ids)),
+                        ),
+                );
+        }
+        if !self.excluded_message_ids.is_empty() {
+            condition = condition.add(
+                entity::message::Column::Id.is_not_in(self.excluded_message_ids.iter().copied()),
+            );
+        }
+        if let Some(sent_after) = self.sent_after {
+            condition = condition.add(entity::message::Column::Timestamp.gte(sent_after));
+        }
+        if let Some(sent_before) = self.sent_before {
+            condition = condition.add(entity::message::Column::Timestamp.lt(sent_before));
+        }
+
+        condition
+    }
+}
+
+/// Condition on the `message` table kept messages satisfy: they are not written by users who
+/// opted out in the guild. Their messages are not saved, but the ones saved before they opted
+/// out can remain.
+fn not_opted_out(guild_id: GuildId) -> Condition {
+    Condition::any()
+        .add(entity::message::Column::AuthorId.is_null())
+        .add(
+            entity::message::Column::AuthorId.not_in_subquery(
+                Query::select()
+                    .column(entity::user_consent::Column::UserId)
+                    .from(UserConsent)
+                    .and_where(entity::user_consent::Column::GuildId.eq(i64::from(guild_id)))
+                    .and_where(entity::user_consent::Column::OptedOut.eq(true))
+                    .to_owned(),
+            ),
+        )
+}
+
+/// Timestamps of the first and the last messages of a period.
+pub type MessagePeriod = (DateTime<FixedOffset>, DateTime<FixedOffset>);
 
 impl Database {
+    /// Saves the messages of a guild and their authors, except the ones written by users who
+    /// opted out.
     pub async fn save_messages(
         &self,
+        guild_id: GuildId,
         discord_messages: &[DiscordMessage],
-        guild_users: &HashSet<&serenity::all::User>,
     ) -> Result<(), DatabaseError> {
-        let new_messages: Vec<entity::message::ActiveModel> = discord_messages
+        let opted_out_users = self.get_opted_out_users(guild_id).await?;
+
+        let discord_messages: Vec<&DiscordMessage> = discord_messages
             .iter()
-            .map(|msg| Self::map_message_to_active_model(msg, guild_users))
+            .filter(|msg| !opted_out_users.contains(&msg.author.id))
+            .collect();
+
+        if discord_messages.is_empty() {
+            return Ok(());
+        }
+
+        // Authors who left the guild are kept, they are not in the member list anymore.
+        let authors: Vec<&serenity::all::User> = discord_messages
+            .iter()
+            .filter(|msg| msg.webhook_id.is_none())
+            .map(|msg| &msg.author)
+            .unique_by(|author| author.id)
+            .collect();
+        self.save_users(&authors).await?;
+
+        let new_messages: Vec<entity::message::ActiveModel> = discord_messages
+            .into_iter()
+            .map(Self::map_message_to_active_model)
             .collect();
 
         match Message::insert_many(new_messages.clone())
             .on_conflict(
                 sea_query::OnConflict::column(entity::message::Column::Id)
-                    .do_nothing()
+                    .update_columns([
+                        entity::message::Column::Content,
+                        entity::message::Column::EditedAt,
+                    ])
                     .to_owned(),
             )
             .exec(&self.db)
@@ -44,6 +162,8 @@ impl Database {
         n_messages: u32,
         minimum_length: u32,
         users_ids: Vec<i64>,
+        scope: &QuoteScope,
+        filters: &QuoteFilters,
     ) -> Result<Vec<entity::message::Model>, DatabaseError> {
         let select = Message::find()
             .join(JoinType::LeftJoin, entity::message::Relation::Channel.def())
@@ -57,6 +177,9 @@ impl Database {
             )
             .to_owned()
             .filter(entity::message::Column::AuthorId.is_in(users_ids))
+            .filter(entity::message::Column::DeletedAt.is_null())
+            .filter(scope.condition())
+            .filter(filters.condition())
             .as_query()
             .to_owned()
             .order_by_expr(SimpleExpr::FunctionCall(Func::random()), Order::Asc)
@@ -71,6 +194,189 @@ impl Database {
             .await?)
     }
 
+    /// Contents of random messages of a user in a guild.
+    pub async fn get_user_message_contents(
+        &self,
+        guild_id: GuildId,
+        user_id: i64,
+        n_messages: u64,
+    ) -> Result<Vec<String>, DatabaseError> {
+        Ok(Message::find()
+            .select_only()
+            .column(entity::message::Column::Content)
+            .join(
+                JoinType::InnerJoin,
+                entity::message::Relation::Channel.def(),
+            )
+            .filter(entity::channel::Column::GuildId.eq(i64::from(guild_id)))
+            .filter(entity::message::Column::AuthorId.eq(user_id))
+            .filter(entity::message::Column::DeletedAt.is_null())
+            .order_by(SimpleExpr::FunctionCall(Func::random()), Order::Asc)
+            .limit(n_messages)
+            .into_tuple()
+            .all(&self.db)
+            .await?)
+    }
+
+    /// Contents of random messages of a guild, other than `message`, a quarter shorter to a quarter
+    /// longer than it. They are picked among the messages which could be quoted, so endings
+    /// offered as choices follow the same rules as the quotes.
+    pub async fn get_messages_of_similar_length(
+        &self,
+        guild_id: GuildId,
+        message: &entity::message::Model,
+        n_messages: u64,
+        users_ids: Vec<i64>,
+        scope: &QuoteScope,
+        filters: &QuoteFilters,
+    ) -> Result<Vec<String>, DatabaseError> {
+        let length = message.content.chars().count() as u32;
+
+        Ok(Message::find()
+            .select_only()
+            .column(entity::message::Column::Content)
+            .join(
+                JoinType::InnerJoin,
+                entity::message::Relation::Channel.def(),
+            )
+            .filter(entity::channel::Column::GuildId.eq(i64::from(guild_id)))
+            .filter(entity::message::Column::Id.ne(message.id))
+            .filter(entity::message::Column::AuthorId.is_in(users_ids))
+            .filter(entity::message::Column::DeletedAt.is_null())
+            .filter(scope.condition())
+            .filter(filters.condition())
+            .filter(
+                Expr::expr(Func::char_length(Expr::col(
+                    entity::message::Column::Content,
+                )))
+                .between(length * 3 / 4, length * 5 / 4),
+            )
+            .order_by(SimpleExpr::FunctionCall(Func::random()), Order::Asc)
+            .limit(n_messages)
+            .into_tuple()
+            .all(&self.db)
+            .await?)
+    }
+
+    /// Timestamps of the first and the last messages of a guild, `None` when it has none.
+    pub async fn get_message_period(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Option<MessagePeriod>, DatabaseError> {
+        let first_and_last = Message::find()
+            .select_only()
+            .column_as(entity::message::Column::Timestamp.min(), "first")
+            .column_as(entity::message::Column::Timestamp.max(), "last")
+            .join(
+                JoinType::InnerJoin,
+                entity::message::Relation::Channel.def(),
+            )
+            .filter(entity::channel::Column::GuildId.eq(i64::from(guild_id)))
+            .filter(entity::message::Column::DeletedAt.is_null())
+            .into_tuple::<(Option<DateTime<FixedOffset>>, Option<DateTime<FixedOffset>>)>()
+            .one(&self.db)
+            .await?;
+
+        Ok(match first_and_last {
+            Some((Some(first), Some(last))) => Some((first, last)),
+            _ => None,
+        })
+    }
+
+    pub async fn update_message(
+        &self,
+        message_id: MessageId,
+        content: &str,
+        edited_at: DateTime<FixedOffset>,
+    ) -> Result<(), DatabaseError> {
+        Message::update_many()
+            .col_expr(entity::message::Column::Content, Expr::value(content))
+            .col_expr(entity::message::Column::EditedAt, Expr::value(edited_at))
+            .filter(entity::message::Column::Id.eq(i64::from(message_id)))
+            .exec(&self.db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Deleted messages are kept so the scrapper does not fetch them again, but they are never
+    /// quoted anymore.
+    pub async fn set_messages_deleted(
+        &self,
+        message_ids: &[MessageId],
+    ) -> Result<(), DatabaseError> {
+        Message::update_many()
+            .col_expr(
+                entity::message::Column::DeletedAt,
+                Expr::value(Local::now().fixed_offset()),
+            )
+            .filter(entity::message::Column::Id.is_in(message_ids.iter().map(|&id| i64::from(id))))
+            .exec(&self.db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Ids of the messages of a channel not marked deleted, from after `after` up to `up_to`.
+    pub async fn get_channel_message_ids(
+        &self,
+        channel_id: ChannelId,
+        after: Option<MessageId>,
+        up_to: MessageId,
+    ) -> Result<Vec<MessageId>, DatabaseError> {
+        let mut select = Message::find()
+            .select_only()
+            .column(entity::message::Column::Id)
+            .filter(entity::message::Column::ChannelId.eq(i64::from(channel_id)))
+            .filter(entity::message::Column::Id.lte(i64::from(up_to)))
+            .filter(entity::message::Column::DeletedAt.is_null());
+
+        if let Some(after) = after {
+            select = select.filter(entity::message::Column::Id.gt(i64::from(after)));
+        }
+
+        let message_ids: Vec<i64> = select.into_tuple().all(&self.db).await?;
+
+        Ok(message_ids
+            .into_iter()
+            .map(|message_id| MessageId::new(message_id as u64))
+            .collect())
+    }
+
+    /// Message sent right before `message` in its channel, skipping the ones of users who opted
+    /// out.
+    pub async fn get_previous_message(
+        &self,
+        guild_id: GuildId,
+        message: &entity::message::Model,
+    ) -> Result<Option<entity::message::Model>, DatabaseError> {
+        Ok(Message::find()
+            .filter(entity::message::Column::ChannelId.eq(message.channel_id))
+            .filter(entity::message::Column::Timestamp.lt(message.timestamp))
+            .filter(entity::message::Column::DeletedAt.is_null())
+            .filter(not_opted_out(guild_id))
+            .order_by_desc(entity::message::Column::Timestamp)
+            .one(&self.db)
+            .await?)
+    }
+
+    /// Message sent right after `message` in its channel, skipping the ones of users who opted
+    /// out.
+    pub async fn get_next_message(
+        &self,
+        guild_id: GuildId,
+        message: &entity::message::Model,
+    ) -> Result<Option<entity::message::Model>, DatabaseError> {
+        Ok(Message::find()
+            .filter(entity::message::Column::ChannelId.eq(message.channel_id))
+            .filter(entity::message::Column::Timestamp.gt(message.timestamp))
+            .filter(entity::message::Column::DeletedAt.is_null())
+            .filter(not_opted_out(guild_id))
+            .order_by_asc(entity::message::Column::Timestamp)
+            .one(&self.db)
+            .await?)
+    }
+
     pub async fn _get_message(
         &self,
         message_id: MessageId,
@@ -83,16 +389,11 @@ impl Database {
 
     fn map_message_to_active_model(
         discord_message: &DiscordMessage,
-        guild_users: &HashSet<&serenity::all::User>,
     ) -> entity::message::ActiveModel {
-        let author_id = if guild_users
-            .iter()
-            .map(|&user| user.id)
-            .contains(&discord_message.author.id)
-        {
-            Some(i64::from(discord_message.author.id))
-        } else {
-            None
+        // Webhook messages are not written by a real user.
+        let author_id = match discord_message.webhook_id {
+            None => Some(i64::from(discord_message.author.id)),
+            Some(_) => None,
         };
 
         entity::message::ActiveModel {
@@ -101,6 +402,12 @@ impl Database {
             timestamp: ActiveValue::Set(discord_message.timestamp.fixed_offset()),
             author_id: ActiveValue::Set(author_id),
             content: ActiveValue::Set(discord_message.content.to_owned()),
+            edited_at: ActiveValue::Set(
+                discord_message
+                    .edited_timestamp
+                    .map(|edited_timestamp| edited_timestamp.fixed_offset()),
+            ),
+            deleted_at: ActiveValue::NotSet,
         }
     }
 }
@@ -3,12 +3,22 @@ use std::env;
 use anyhow::Result;
 use sea_orm::{Database as SeaOrmDatabase, DatabaseConnection};
 
+mod activity;
 mod channel;
+mod consent;
 pub(crate) mod error;
+pub(crate) mod game;
 mod guild;
-mod messages;
+pub(crate) mod guild_config;
+mod guild_member;
+pub(crate) mod messages;
+pub(crate) mod quote_filter;
+mod scrape_channel;
 pub(crate) mod user;
 
+/// Rows per multi-row insert, keeps statements under the postgres limit of 65535 bind parameters.
+const INSERT_BATCH_SIZE: usize = 1000;
+
 pub struct Database {
     db: DatabaseConnection,
 }
@@ -0,0 +1,239 @@
+use sea_orm::{
+    ActiveValue, ColumnTrait, ConnectionTrait, DbBackend, DbErr, EntityTrait, QueryFilter,
+    QueryOrder, RuntimeErr, Statement,
+};
+use sea_query::{Condition, Expr, Value};
+use serenity::all::GuildId;
+
+use entity::prelude::*;
+
+use crate::database::Database;
+use crate::database::error::DatabaseError;
+
+/// Links, as counted by [`QuoteFilters::skip_links`].
+const LINK_PATTERN: &str = r"https?://\S+";
+/// Messages starting like a command of a bot, as `!play` or `?rank`.
+const COMMAND_PATTERN: &str = r"^[!?$%;~.][[:alpha:]]";
+/// Custom emoji, unicode emoji and the characters emoji are composed with, and blanks.
+const EMOJI_PATTERN: &str =
+    r"<a?:\w+:\d+>|[\u2190-\u21FF\u2300-\u27BF\u2B00-\u2BFF\uFE0F\u200D\u20E3]|[\U0001F000-\U0001FAFF]|\s";
+const WORD_SEPARATOR_PATTERN: &str = r"\s+";
+/// Error code of postgres for invalid regular expressions.
+const INVALID_REGULAR_EXPRESSION: &str = "2201B";
+
+/// What makes a message of a guild unfit to be quoted.
+pub struct QuoteFilters {
+    /// Skip messages which are mostly links.
+    pub skip_links: bool,
+    /// Skip messages which look like commands for other bots.
+    pub skip_commands: bool,
+    /// Skip messages made only of emoji.
+    pub skip_emoji_only: bool,
+    /// Skip messages several people sent with the same content, like copy-pastas, which have no
+    /// single author.
+    pub skip_duplicates: bool,
+    pub minimum_words: u32,
+    /// Regular expressions of postgres, the messages they match are skipped whatever their case.
+    pub patterns: Vec<String>,
+}
+
+impl Default for QuoteFilters {
+    fn default() -> Self {
+        Self {
+            skip_links: true,
+            skip_commands: true,
+            skip_emoji_only: true,
+            skip_duplicates: true,
+            minimum_words: 1,
+            patterns: vec![],
+        }
+    }
+}
+
+impl QuoteFilters {
+    /// Condition on the `message` table, joined with its `channel`, kept messages satisfy.
+    pub(crate) fn condition(&self) -> Condition {
+        let mut condition = Condition::all();
+
+        if self.skip_links {
+            condition = condition.add(Expr::cust_with_values(
+                r#"char_length(regexp_replace("message"."content", $1, '', 'g')) * 2 >= char_length("message"."content")"#,
+                [LINK_PATTERN],
+            ));
+        }
+        if self.skip_commands {
+            condition = condition.add(Expr::cust_with_values(
+                r#""message"."content" !~ $1"#,
+                [COMMAND_PATTERN],
+            ));
+        }
+        if self.skip_emoji_only {
+            condition = condition.add(Expr::cust_with_values(
+                r#"regexp_replace("message"."content", $1, '', 'g') <> ''"#,
+                [EMOJI_PATTERN],
+            ));
+        }
+        if self.skip_duplicates {
+            condition = condition.add(Expr::cust(
+                r#"NOT EXISTS (
+                    SELECT 1 FROM "message" AS "copy"
+                    INNER JOIN "channel" AS "copy_channel" ON "copy_channel"."id" = "copy"."channel_id"
+                    WHERE md5("copy"."content") = md5("message"."content")
+                    AND "copy"."content" = "message"."content"
+                    AND "copy"."author_id" <> "message"."author_id"
+                    AND "copy_channel"."guild_id" = "channel"."guild_id"
+                )"#,
+            ));
+        }
+        if self.minimum_words > 1 {
+            condition = condition.add(Expr::cust_with_values(
+                r#"array_length(regexp_split_to_array(btrim("message"."content"), $1), 1) >= $2"#,
+                [
+                    Value::from(WORD_SEPARATOR_PATTERN),
+                    Value::from(self.minimum_words),
+                ],
+            ));
+        }
+        for pattern in &self.patterns {
+            condition = condition.add(Expr::cust_with_values(
+                r#""message"."content" !~* $1"#,
+                [pattern.as_str()],
+            ));
+        }
+
+        condition
+    }
+}
+
+impl Database {
+    pub async fn get_quote_filters(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<QuoteFilters, DatabaseError> {
+        let settings = QuoteFilter::find_by_id(i64::from(guild_id))
+            .one(&self.db)
+            .await?;
+
+        let patterns = QuoteFilterPattern::find()
+            .filter(entity::quote_filter_pattern::Column::GuildId.eq(i64::from(guild_id)))
+            .order_by_asc(entity::quote_filter_pattern::Column::Id)
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .map(|pattern| pattern.pattern)
+            .collect();
+
+        Ok(match settings {
+            Some(settings) => QuoteFilters {
+                skip_links: settings.skip_links,
+                skip_commands: settings.skip_commands,
+                skip_emoji_only: settings.skip_emoji_only,
+                skip_duplicates: settings.skip_duplicates,
+                minimum_words: settings.minimum_words as u32,
+                patterns,
+            },
+            None => QuoteFilters {
+                patterns,
+                ..Default::default()
+            },
+        })
+    }
+
+    /// Saves the settings of the filters, patterns are added and removed on their own.
+    pub async fn save_quote_filters(
+        &self,
+        guild_id: GuildId,
+        filters: &QuoteFilters,
+    ) -> Result<(), DatabaseError> {
+        let settings = entity::quote_filter::ActiveModel {
+            guild_id: ActiveValue::Set(guild_id.into()),
+            skip_links: ActiveValue::Set(filters.skip_links),
+            skip_commands: ActiveValue::Set(filters.skip_commands),
+            skip_emoji_only: ActiveValue::Set(filters.skip_emoji_only),
+            skip_duplicates: ActiveValue::Set(filters.skip_duplicates),
+            minimum_words: ActiveValue::Set(filters.minimum_words as i32),
+        };
+
+        QuoteFilter::insert(settings)
+            .on_conflict(
+                sea_query::OnConflict::column(entity::quote_filter::Column::GuildId)
+                    .update_columns([
+                        entity::quote_filter::Column::SkipLinks,
+                        entity::quote_filter::Column::SkipCommands,
+                        entity::quote_filter::Column::SkipEmojiOnly,
+                        entity::quote_filter::Column::SkipDuplicates,
+                        entity::quote_filter::Column::MinimumWords,
+                    ])
+                    .to_owned(),
+            )
+            .exec(&self.db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns whether the pattern was added, it is not when the guild already has it.
+    pub async fn add_quote_filter_pattern(
+        &self,
+        guild_id: GuildId,
+        pattern: &str,
+    ) -> Result<bool, DatabaseError> {
+        let new_pattern = entity::quote_filter_pattern::ActiveModel {
+            guild_id: ActiveValue::Set(guild_id.into()),
+            pattern: ActiveValue::Set(pattern.to_string()),
+            ..Default::default()
+        };
+
+        match QuoteFilterPattern::insert(new_pattern)
+            .on_conflict(
+                sea_query::OnConflict::columns([
+                    entity::quote_filter_pattern::Column::GuildId,
+                    entity::quote_filter_pattern::Column::Pattern,
+                ])
+                .do_nothing()
+                .to_owned(),
+            )
+            .exec(&self.db)
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(DbErr::RecordNotInserted) => Ok(false),
+            Err(err) => Err(DatabaseError::from(err)),
+        }
+    }
+
+    /// Returns whether the guild had the pattern.
+    pub async fn remove_quote_filter_pattern(
+        &self,
+        guild_id: GuildId,
+        pattern: &str,
+    ) -> Result<bool, DatabaseError> {
+        let result = QuoteFilterPattern::delete_many()
+            .filter(entity::quote_filter_pattern::Column::GuildId.eq(i64::from(guild_id)))
+            .filter(entity::quote_filter_pattern::Column::Pattern.eq(pattern))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+
+    /// Whether postgres accepts the pattern as a regular expression.
+    pub async fn is_valid_pattern(&self, pattern: &str) -> Result<bool, DatabaseError> {
+        let statement = Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            "SELECT '' ~* $1",
+            [pattern.into()],
+        );
+
+        match self.db.query_one(statement).await {
+            Ok(_) => Ok(true),
+            Err(DbErr::Query(RuntimeErr::SqlxError(sqlx::Error::Database(error))))
+            | Err(DbErr::Exec(RuntimeErr::SqlxError(sqlx::Error::Database(error))))
+                if error.code().as_deref() == Some(INVALID_REGULAR_EXPRESSION) =>
+            {
+                Ok(false)
+            }
+            Err(err) => Err(DatabaseError::from(err)),
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use sea_orm::{ActiveValue, ColumnTrait, EntityTrait, QueryFilter, QuerySelect};
+use serenity::all::{ChannelId, GuildId};
+
+use entity::prelude::*;
+
+use crate::database::Database;
+use crate::database::error::DatabaseError;
+
+impl Database {
+    /// Whether the messages of a saved channel are scraped: as its own rule says, or the rule of
+    /// the channel of a thread, and unless the channel is NSFW otherwise.
+    pub async fn is_channel_scraped(&self, channel_id: ChannelId) -> Result<bool, DatabaseError> {
+        let channel = self.get_channel(channel_id).await?;
+        let parent = match channel.parent_id {
+            Some(parent_id) => Channel::find_by_id(parent_id).one(&self.db).await?,
+            None => None,
+        };
+
+        let rules = GuildScrapeChannel::find()
+            .filter(entity::guild_scrape_channel::Column::GuildId.eq(channel.guild_id))
+            .filter(
+                entity::guild_scrape_channel::Column::ChannelId
+                    .is_in([Some(channel.id), channel.parent_id].into_iter().flatten()),
+            )
+            .all(&self.db)
+            .await?;
+
+        let rule = |channel_id: i64| {
+            rules
+                .iter()
+                .find(|rule| rule.channel_id == channel_id)
+                .map(|rule| rule.scraped)
+        };
+
+        Ok(rule(channel.id)
+            .or_else(|| channel.parent_id.and_then(rule))
+            .unwrap_or(!channel.nsfw && !parent.is_some_and(|parent| parent.nsfw)))
+    }
+
+    /// Always scrapes the channel and its threads, NSFW or not, or never scrapes them.
+    pub async fn save_scrape_channel(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        scraped: bool,
+    ) -> Result<(), DatabaseError> {
+        let channel = entity::guild_scrape_channel::ActiveModel {
+            guild_id: ActiveValue::Set(guild_id.into()),
+            channel_id: ActiveValue::Set(channel_id.into()),
+            scraped: ActiveValue::Set(scraped),
+        };
+
+        GuildScrapeChannel::insert(channel)
+            .on_conflict(
+                sea_query::OnConflict::columns([
+                    entity::guild_scrape_channel::Column::GuildId,
+                    entity::guild_scrape_channel::Column::ChannelId,
+                ])
+                .update_column(entity::guild_scrape_channel::Column::Scraped)
+                .to_owned(),
+            )
+            .exec(&self.db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns whether the channel had a rule of its own.
+    pub async fn remove_scrape_channel(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+    ) -> Result<bool, DatabaseError> {
+        let result = GuildScrapeChannel::delete_many()
+            .filter(entity::guild_scrape_channel::Column::GuildId.eq(i64::from(guild_id)))
+            .filter(entity::guild_scrape_channel::Column::ChannelId.eq(i64::from(channel_id)))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+
+    /// Saved threads of a channel.
+    pub async fn get_channel_threads(
+        &self,
+        channel_id: ChannelId,
+    ) -> Result<Vec<ChannelId>, DatabaseError> {
+        let thread_ids: Vec<i64> = Channel::find()
+            .select_only()
+            .column(entity::channel::Column::Id)
+            .filter(entity::channel::Column::ParentId.eq(i64::from(channel_id)))
+            .into_tuple()
+            .all(&self.db)
+            .await?;
+
+        Ok(thread_ids
+            .into_iter()
+            .map(|thread_id| ChannelId::new(thread_id as u64))
+            .collect())
+    }
+
+    /// Removes the messages of a channel, a channel scraped again is backfilled from its start.
+    pub async fn purge_channel(&self, channel_id: ChannelId) -> Result<(), DatabaseError> {
+        Message::delete_many()
+            .filter(entity::message::Column::ChannelId.eq(i64::from(channel_id)))
+            .exec(&self.db)
+            .await?;
+
+        let updated = entity::channel::ActiveModel {
+            id: ActiveValue::Set(i64::from(channel_id)),
+            backfill_done: ActiveValue::Set(false),
+            ..Default::default()
+        };
+
+        Channel::update(updated).exec(&self.db).await?;
+
+        Ok(())
+    }
+}
@@ -1,14 +1,16 @@
+use std::collections::HashMap;
+
 use sea_orm::{
     ActiveValue, ColumnTrait, DbErr, EntityTrait, FromQueryResult, QueryFilter, QueryOrder,
-    QuerySelect, RelationTrait,
+    QuerySelect, RelationTrait, SqlErr,
 };
-use sea_query::JoinType;
+use sea_query::{Expr, IntoCondition, JoinType, Query};
 use serenity::all::{GuildId, User as DiscordUser, UserId};
 
 use entity::prelude::*;
 
-use crate::database::Database;
 use crate::database::error::DatabaseError;
+use crate::database::{Database, INSERT_BATCH_SIZE};
 
 #[derive(FromQueryResult)]
 pub struct UserWithEmoji {
@@ -17,7 +19,8 @@ pub struct UserWithEmoji {
     // pub(crate) discriminator: Option<u32>,
     // pub(crate) global_name: Option<String>,
     // pub(crate) bot: Option<bool>,
-    // pub(crate) emoji: String,
+    /// Emoji set with `/emoji` in the guild.
+    pub(crate) emoji: Option<String>,
 }
 
 impl Database {
@@ -37,46 +40,93 @@ impl Database {
         }
     }
     pub async fn save_users(&self, discord_users: &Vec<&DiscordUser>) -> Result<(), DatabaseError> {
-        let new_users: Vec<entity::user::ActiveModel> = discord_users
-            .iter()
-            .map(|discord_user: &&serenity::all::User| Self::map_user_to_active_model(discord_user))
-            .collect();
+        for discord_users in discord_users.chunks(INSERT_BATCH_SIZE) {
+            let new_users: Vec<entity::user::ActiveModel> = discord_users
+                .iter()
+                .map(|discord_user: &&serenity::all::User| {
+                    Self::map_user_to_active_model(discord_user)
+                })
+                .collect();
 
-        match User::insert_many(new_users)
-            .on_conflict(
-                sea_query::OnConflict::column(entity::user::Column::Id)
-                    .do_nothing()
-                    .to_owned(),
-            )
-            .exec(&self.db)
-            .await
-        {
-            Ok(_) => Ok(()),
-            Err(DbErr::RecordNotInserted) => Ok(()),
-            Err(err) => Err(DatabaseError::from(err)),
+            match User::insert_many(new_users)
+                .on_conflict(
+                    sea_query::OnConflict::column(entity::user::Column::Id)
+                        .do_nothing()
+                        .to_owned(),
+                )
+                .exec(&self.db)
+                .await
+            {
+                Ok(_) => {}
+                Err(DbErr::RecordNotInserted) => {}
+                Err(err) => return Err(DatabaseError::from(err)),
+            }
         }
+
+        Ok(())
     }
 
     pub async fn get_most_active_users_with_emoji(
         &self,
         guild_id: GuildId,
         n_most_active_users: u32,
+        include_former_members: bool,
+        with_emoji_only: bool,
     ) -> Result<Vec<UserWithEmoji>, DatabaseError> {
-        let select = User::find()
+        let mut select = User::find()
+            .column_as(entity::user_emoji::Column::Emoji, "emoji")
             .join_rev(JoinType::LeftJoin, entity::message::Relation::User.def())
             .join(JoinType::LeftJoin, entity::message::Relation::Channel.def())
+            .join(
+                JoinType::LeftJoin,
+                entity::user::Relation::UserEmoji
+                    .def()
+                    .on_condition(move |_left, right| {
+                        Expr::col((right, entity::user_emoji::Column::GuildId))
+                            .eq(i64::from(guild_id))
+                            .into_condition()
+                    }),
+            )
             .filter(entity::channel::Column::GuildId.eq(i64::from(guild_id)))
             .filter(entity::user::Column::Bot.eq(false))
+            .filter(
+                entity::user::Column::Id.not_in_subquery(
+                    Query::select()
+                        .column(entity::user_consent::Column::UserId)
+                        .from(UserConsent)
+                        .and_where(entity::user_consent::Column::GuildId.eq(i64::from(guild_id)))
+                        .and_where(entity::user_consent::Column::OptedOut.eq(true))
+                        .to_owned(),
+                ),
+            )
             .group_by(entity::user::Column::Id)
+            .group_by(entity::user_emoji::Column::Emoji)
             .order_by_desc(entity::message::Column::Id.count())
-            .limit(n_most_active_users as u64)
-            .into_model::<UserWithEmoji>()
-            .all(&self.db)
-            .await?;
+            .limit(n_most_active_users as u64);
 
-        Ok(select)
+        if with_emoji_only {
+            select = select.filter(entity::user_emoji::Column::Emoji.is_not_null());
+        }
+
+        if !include_former_members {
+            select = select.filter(
+                entity::user::Column::Id.in_subquery(
+                    Query::select()
+                        .column(entity::guild_member::Column::UserId)
+                        .from(GuildMember)
+                        .and_where(entity::guild_member::Column::GuildId.eq(i64::from(guild_id)))
+                        .and_where(entity::guild_member::Column::LeftAt.is_null())
+                        .to_owned(),
+                ),
+            );
+        }
+
+        let users = select.into_model::<UserWithEmoji>().all(&self.db).await?;
+
+        Ok(users)
     }
 
+    /// Fails with [`DatabaseError::EmojiClaimed`] when another user of the guild has this emoji.
     pub async fn save_user_emoji(
         &self,
         user_id: UserId,
@@ -103,10 +153,64 @@ impl Database {
         {
             Ok(_) => Ok(()),
             Err(DbErr::RecordNotInserted) => Ok(()),
+            Err(err) if matches!(err.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => {
+                match self.get_emoji_owner(guild_id, emoji).await? {
+                    Some(owner) => Err(DatabaseError::EmojiClaimed(owner)),
+                    None => Err(DatabaseError::from(err)),
+                }
+            }
             Err(err) => Err(DatabaseError::from(err)),
         }
     }
 
+    pub async fn get_user_names(
+        &self,
+        user_ids: &[i64],
+    ) -> Result<HashMap<i64, String>, DatabaseError> {
+        if user_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let names: Vec<(i64, String)> = User::find()
+            .select_only()
+            .column(entity::user::Column::Id)
+            .column(entity::user::Column::Name)
+            .filter(entity::user::Column::Id.is_in(user_ids.iter().copied()))
+            .into_tuple()
+            .all(&self.db)
+            .await?;
+
+        Ok(names.into_iter().collect())
+    }
+
+    pub async fn remove_user_emoji(
+        &self,
+        user_id: UserId,
+        guild_id: GuildId,
+    ) -> Result<(), DatabaseError> {
+        UserEmoji::delete_many()
+            .filter(entity::user_emoji::Column::UserId.eq(i64::from(user_id)))
+            .filter(entity::user_emoji::Column::GuildId.eq(i64::from(guild_id)))
+            .exec(&self.db)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_emoji_owner(
+        &self,
+        guild_id: GuildId,
+        emoji: &str,
+    ) -> Result<Option<UserId>, DatabaseError> {
+        let user_emoji = UserEmoji::find()
+            .filter(entity::user_emoji::Column::GuildId.eq(i64::from(guild_id)))
+            .filter(entity::user_emoji::Column::Emoji.eq(emoji))
+            .one(&self.db)
+            .await?;
+
+        Ok(user_emoji.map(|user_emoji| UserId::new(user_emoji.user_id as u64)))
+    }
+
     fn map_user_to_active_model(discord_user: &DiscordUser) -> entity::user::ActiveModel {
         entity::user::ActiveModel {
             id: ActiveValue::Set(discord_user.id.into()),
@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use rand::seq::SliceRandom;
+
+use entity::message;
+
+use crate::database::Database;
+use crate::database::error::DatabaseError;
+use crate::database::user::UserWithEmoji;
+use crate::game::options::DistractorStrategy;
+
+/// Picks the possible responses shown for a quote: its author and `n_choices - 1` other users,
+/// in a random order.
+pub async fn pick_choices(
+    database: &Database,
+    quote: &message::Model,
+    users: &[UserWithEmoji],
+    n_choices: usize,
+    strategy: DistractorStrategy,
+) -> Result<Vec<i64>, DatabaseError> {
+    let candidates: Vec<i64> = users
+        .iter()
+        .map(|user| user.id)
+        .filter(|id| quote.author_id != Some(*id))
+        .collect();
+
+    let scores = match (strategy, quote.author_id) {
+        (DistractorStrategy::Random, _) | (_, None) => None,
+        (DistractorStrategy::SameChannel, Some(author_id)) => {
+            let mut user_ids = candidates.clone();
+            user_ids.push(author_id);
+
+            let activity = database
+                .get_channel_activity(quote.channel_id, &user_ids)
+                .await?;
+            let author_activity = activity.get(&author_id).copied().unwrap_or_default();
+
+            // The closer their activity is to the author's, the more plausible they are.
+            Some(
+                candidates
+                    .iter()
+                    .map(|id| {
+                        let gap = activity.get(id).copied().unwrap_or_default() - author_activity;
+                        (*id, -gap.abs())
+                    })
+                    .collect(),
+            )
+        }
+        (DistractorStrategy::Conversation, Some(author_id)) => Some(
+            database
+                .get_conversation_partners(quote.channel_id, author_id)
+                .await?,
+        ),
+    };
+
+    Ok(choose(quote.author_id, candidates, scores, n_choices))
+}
+
+/// Keeps the candidates with the best scores, ties and unscored candidates are broken randomly.
+fn choose(
+    author_id: Option<i64>,
+    mut candidates: Vec<i64>,
+    scores: Option<HashMap<i64, i64>>,
+    n_choices: usize,
+) -> Vec<i64> {
+    let mut rng = rand::thread_rng();
+
+    candidates.shuffle(&mut rng);
+
+    let mut choices: Vec<i64> = match scores {
+        None => candidates,
+        Some(scores) => candidates
+            .into_iter()
+            .sorted_by_key(|id| std::cmp::Reverse(scores.get(id).copied().unwrap_or(i64::MIN)))
+            .collect(),
+    };
+
+    choices.truncate(n_choices.saturating_sub(1));
+    choices.extend(author_id);
+    choices.shuffle(&mut rng);
+
+    choices
+}
@@ -0,0 +1,60 @@
+use chrono::Datelike;
+use serenity::all::{ChannelId, GuildId};
+
+use entity::message;
+
+use crate::database::Database;
+use crate::database::error::DatabaseError;
+use crate::game::question::QuestionKind;
+use crate::game::render;
+
+/// Hints of a quote, from the vaguest to the most telling: the year it was said, its channel and
+/// the messages sent around it. Hints giving away the answer of the question are left out.
+pub async fn load_hints(
+    database: &Database,
+    guild_id: GuildId,
+    quote: &message::Model,
+    kind: QuestionKind,
+) -> Result<Vec<String>, DatabaseError> {
+    // Any context would tell real quotes apart from generated ones.
+    if kind == QuestionKind::RealOrFake {
+        return Ok(vec![]);
+    }
+
+    let mut hints = vec![];
+
+    if !matches!(kind, QuestionKind::Year | QuestionKind::Month) {
+        hints.push(format!("It was said in {}", quote.timestamp.year()));
+    }
+
+    if kind != QuestionKind::Channel {
+        let channel_id = ChannelId::new(quote.channel_id as u64);
+        match database.get_channel(channel_id).await {
+            Ok(channel) => hints.push(format!("It was said in #{}", channel.name)),
+            Err(DatabaseError::NotFound) => {}
+            Err(err) => return Err(err),
+        }
+    }
+
+    let previous = database.get_previous_message(guild_id, quote).await?;
+    if let Some(previous) = previous.filter(|message| !message.content.is_empty()) {
+        hints.push(format!(
+            "The message before was:\n{}",
+            excerpt(&previous.content)
+        ));
+    }
+
+    let next = database.get_next_message(guild_id, quote).await?;
+    if let Some(next) = next.filter(|message| !message.content.is_empty()) {
+        hints.push(format!(
+            "The message after was:\n{}",
+            excerpt(&next.content)
+        ));
+    }
+
+    Ok(hints)
+}
+
+fn excerpt(content: &str) -> String {
+    render::block_quote(&render::truncate(content, render::MAX_EXCERPT_LENGTH))
+}
@@ -0,0 +1,79 @@
+use std::collections::{HashMap, HashSet};
+
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+/// Messages of a user a chain imitating them is built from.
+pub const CORPUS_SIZE: u64 = 2000;
+/// Words a generated word depends on.
+const ORDER: usize = 2;
+/// Generated quotes are cut after this many words.
+const MAX_WORDS: usize = 60;
+/// Generations tried before giving up on imitating a user.
+const MAX_ATTEMPTS: usize = 50;
+
+/// Word level Markov chain imitating the messages it was built from.
+pub struct MarkovChain {
+    /// Words following each sequence of [`ORDER`] words, `None` where a message ended.
+    transitions: HashMap<Vec<String>, Vec<Option<String>>>,
+    /// First [`ORDER`] words of the messages.
+    starts: Vec<Vec<String>>,
+    /// The messages, generated quotes must not be one of them.
+    messages: HashSet<String>,
+}
+
+impl MarkovChain {
+    pub fn new(messages: &[String]) -> Self {
+        let mut transitions: HashMap<Vec<String>, Vec<Option<String>>> = HashMap::new();
+        let mut starts = vec![];
+
+        for message in messages {
+            let words: Vec<String> = message.split_whitespace().map(str::to_string).collect();
+
+            if words.len() <= ORDER {
+                continue;
+            }
+
+            starts.push(words[..ORDER].to_vec());
+
+            for i in ORDER..=words.len() {
+                transitions
+                    .entry(words[i - ORDER..i].to_vec())
+                    .or_default()
+                    .push(words.get(i).cloned());
+            }
+        }
+
+        Self {
+            transitions,
+            starts,
+            messages: messages
+                .iter()
+                .map(|message| message.trim().to_string())
+                .collect(),
+        }
+    }
+
+    /// A new message of at least `minimum_length` characters, `None` when the messages are too
+    /// few or too alike to make one.
+    pub fn generate(&self, rng: &mut impl Rng, minimum_length: usize) -> Option<String> {
+        (0..MAX_ATTEMPTS)
+            .filter_map(|_| self.walk(rng))
+            .find(|text| text.chars().count() >= minimum_length && !self.messages.contains(text))
+    }
+
+    fn walk(&self, rng: &mut impl Rng) -> Option<String> {
+        let mut words = self.starts.choose(rng)?.clone();
+
+        while words.len() < MAX_WORDS {
+            let state = &words[words.len() - ORDER..];
+
+            match self.transitions.get(state)?.choose(rng)? {
+                Some(word) => words.push(word.clone()),
+                None => break,
+            }
+        }
+
+        Some(words.join(" "))
+    }
+}
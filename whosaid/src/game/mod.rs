@@ -1,47 +1,806 @@
+use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::Duration;
 
-use serenity::all::GuildId;
+use chrono::{DateTime, FixedOffset, TimeDelta};
+use itertools::Itertools;
+use rand::seq::SliceRandom;
+use serenity::all::{GuildId, UserId};
 
 use entity::message;
 
 use crate::database::Database;
 use crate::database::error::DatabaseError;
+use crate::database::messages::QuoteScope;
 use crate::database::user::UserWithEmoji;
+use crate::game::markov::MarkovChain;
+use crate::game::options::{GameOptions, MissingEmoji};
+use crate::game::question::{Choice, Question, QuestionKind};
+use crate::game::score::Scoreboard;
 
+pub mod choices;
+pub mod hints;
+pub mod markov;
+pub mod options;
+pub mod question;
+pub mod registry;
+pub mod render;
+pub mod score;
+
+/// Default time between the start announcement and the first question, for games without a
+/// lobby.
+pub const LOBBY_DURATION: Duration = Duration::from_secs(5);
+/// Default time the answer of a question stays on screen before the next question.
+pub const REVEAL_DURATION: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    /// Players get ready, no question has been asked yet.
+    Lobby,
+    /// The question at `index` is asked and accepts answers.
+    Question {
+        index: usize,
+        asked_at: DateTime<FixedOffset>,
+    },
+    /// The answer of the question at `index` is shown.
+    Reveal {
+        index: usize,
+    },
+    Finished,
+}
+
+pub struct Answer {
+    pub user_id: UserId,
+    /// Id of the picked choice.
+    pub guess: Option<i64>,
+    pub correct: bool,
+    pub response_time: TimeDelta,
+    pub points: i64,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AnswerError {
+    #[error("The question does not accept answers anymore")]
+    NotAcceptingAnswers,
+    #[error("Your answer is already locked in")]
+    AlreadyAnswered,
+    #[error("You did not join this game")]
+    NotJoined,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum JoinError {
+    #[error("This game has no lobby, anyone can answer")]
+    NoLobby,
+    #[error("The game already started")]
+    AlreadyStarted,
+    #[error("You already joined")]
+    AlreadyJoined,
+}
+
+/// The rules of a game: the order of its phases, the evaluation of the answers and the scores.
+/// Time is given by the caller, which is in charge of waiting for each phase to end.
 pub struct Game {
-    quotes: Vec<message::Model>,
-    users: Vec<UserWithEmoji>,
+    questions: Vec<Question>,
+    /// Number of hints shown on the current question.
+    hints_shown: usize,
+    answer_window: Duration,
+    /// Countdown of the lobby, games without one start after `start_delay`.
+    lobby_countdown: Option<Duration>,
+    start_delay: Duration,
+    reveal_duration: Duration,
+    /// Players who joined in the lobby, in the order they joined. Anyone can answer in games
+    /// without a lobby.
+    players: Option<Vec<UserId>>,
+    phase: Phase,
+    /// Answers to the current question, in the order they were given.
+    answers: Vec<Answer>,
+    scoreboard: Scoreboard,
+    stopped: bool,
 }
 
 impl Game {
-    pub async fn new(
+    pub fn new(questions: Vec<Question>, answer_window: Duration) -> Self {
+        Self {
+            questions,
+            hints_shown: 0,
+            answer_window,
+            lobby_countdown: None,
+            start_delay: LOBBY_DURATION,
+            reveal_duration: REVEAL_DURATION,
+            players: None,
+            phase: Phase::Lobby,
+            answers: vec![],
+            scoreboard: Scoreboard::default(),
+            stopped: false,
+        }
+    }
+
+    /// Picks the possible responses and the quotes of a game.
+    pub async fn load(
         database: Arc<Database>,
         guild_id: GuildId,
-        n_questions: u32,
-        minimum_quote_length: u32,
-        n_most_active_users: u32,
+        options: &GameOptions,
     ) -> Result<Self, DatabaseError> {
         let users = database
-            .get_most_active_users_with_emoji(guild_id, n_most_active_users)
+            .get_most_active_users_with_emoji(
+                guild_id,
+                options.n_choices,
+                options.include_former_members,
+                options.missing_emoji == MissingEmoji::Exclude,
+            )
             .await?;
 
+        let users = with_fallback_emoji(users);
+
+        let mut scope = QuoteScope {
+            channel_id: options.channel_id,
+            sent_after: options.sent_after(),
+            sent_before: options.sent_before(),
+            allowed_channel_ids: options.allowed_channels.clone(),
+            denied_channel_ids: options.denied_channels.clone(),
+            ..Default::default()
+        };
+        let filters = database.get_quote_filters(guild_id).await?;
+
+        // Questions asking how a quote ends need long quotes, they are picked apart.
+        let n_long_quotes = match options
+            .question_kinds
+            .contains(&QuestionKind::FinishSentence)
+        {
+            true => (0..options.n_questions)
+                .filter(|_| pick_kind(&options.question_kinds) == QuestionKind::FinishSentence)
+                .count() as u32,
+            false => 0,
+        };
+        let other_kinds: Vec<QuestionKind> = options
+            .question_kinds
+            .iter()
+            .copied()
+            .filter(|kind| *kind != QuestionKind::FinishSentence)
+            .collect();
+
+        let long_quotes = database
+            .get_random_messages(
+                guild_id,
+                n_long_quotes,
+                options
+                    .minimum_quote_length
+                    .max(question::FINISH_SENTENCE_MIN_LENGTH),
+                users.iter().map(|u| u.id).collect(),
+                &scope,
+                &filters,
+            )
+            .await?;
+        // The long quotes could be picked again, and asked twice.
+        scope.excluded_message_ids = long_quotes.iter().map(|quote| quote.id).collect();
         let quotes = database
             .get_random_messages(
                 guild_id,
-                n_questions,
-                minimum_quote_length,
+                options.n_questions - long_quotes.len() as u32,
+                options.minimum_quote_length,
                 users.iter().map(|u| u.id).collect(),
+                &scope,
+                &filters,
             )
             .await?;
 
-        Ok(Self { quotes, users })
+        let mut quotes: Vec<(QuestionKind, message::Model)> = long_quotes
+            .into_iter()
+            .map(|quote| (QuestionKind::FinishSentence, quote))
+            .chain(
+                quotes
+                    .into_iter()
+                    .map(|quote| (pick_kind(&other_kinds), quote)),
+            )
+            .collect();
+        quotes.shuffle(&mut rand::thread_rng());
+
+        let channels = match options.question_kinds.contains(&QuestionKind::Channel) {
+            true => database.get_quotable_channels(guild_id, &scope).await?,
+            false => vec![],
+        };
+        let period = match options
+            .question_kinds
+            .iter()
+            .any(|kind| matches!(kind, QuestionKind::Year | QuestionKind::Month))
+        {
+            true => database.get_message_period(guild_id).await?,
+            false => None,
+        };
+
+        let mut questions = vec![];
+
+        for (kind, quote) in quotes {
+            let n_choices = options.choices_per_question.unwrap_or(options.n_choices) as usize;
+
+            // Quotes that cannot be asked about the picked kind, for lack of choices, are asked
+            // about their author.
+            let question = match kind {
+                QuestionKind::Author | QuestionKind::RealOrFake => None,
+                QuestionKind::FinishSentence => {
+                    let other_messages = database
+                        .get_messages_of_similar_length(
+                            guild_id,
+                            &quote,
+                            question::FINISH_SENTENCE_WRONG_ENDINGS * 2,
+                            users.iter().map(|u| u.id).collect(),
+                            &scope,
+                            &filters,
+                        )
+                        .await?;
+
+                    Question::finish_sentence(quote.clone(), &other_messages)
+                }
+                QuestionKind::Channel => Question::channel(quote.clone(), &channels, n_choices),
+                QuestionKind::Year => {
+                    period.and_then(|period| Question::year(quote.clone(), period, n_choices))
+                }
+                QuestionKind::Month => {
+                    period.and_then(|period| Question::month(quote.clone(), period, n_choices))
+                }
+            };
+
+            let question = match question {
+                Some(question) => question,
+                None if kind == QuestionKind::RealOrFake => {
+                    let choices = pick_users(
+                        &database,
+                        &quote,
+                        &users,
+                        options,
+                        question::MAX_REAL_OR_FAKE_USERS,
+                    )
+                    .await?;
+
+                    let fake = match (rand::random::<bool>(), quote.author_id) {
+                        (true, Some(author_id)) => {
+                            let messages = database
+                                .get_user_message_contents(guild_id, author_id, markov::CORPUS_SIZE)
+                                .await?;
+
+                            MarkovChain::new(&messages).generate(
+                                &mut rand::thread_rng(),
+                                options.minimum_quote_length as usize,
+                            )
+                        }
+                        _ => None,
+                    };
+
+                    Question::real_or_fake(quote, &choices, fake)
+                }
+                None => {
+                    let choices =
+                        pick_users(&database, &quote, &users, options, users.len()).await?;
+
+                    Question::author(quote, &choices)
+                }
+            };
+
+            let question = match options.hints {
+                true => {
+                    let hints =
+                        hints::load_hints(&database, guild_id, &question.quote, question.kind)
+                            .await?;
+                    question.with_hints(hints)
+                }
+                false => question,
+            };
+
+            questions.push(question);
+        }
+
+        // Quotes mention people by id, and could ping them when shown.
+        let mentioned_user_ids: Vec<i64> = questions
+            .iter_mut()
+            .flat_map(Question::texts_mut)
+            .flat_map(|text| render::mentioned_user_ids(text))
+            .unique()
+            .collect();
+        let names = database.get_user_names(&mentioned_user_ids).await?;
+
+        for text in questions.iter_mut().flat_map(Question::texts_mut) {
+            *text = render::neutralise_mentions(text, &names);
+        }
+
+        let game = Self::new(questions, options.answer_timeout)
+            .with_pauses(options.start_delay, options.reveal_duration);
+
+        Ok(match options.lobby {
+            true => game.with_lobby(options.lobby_countdown),
+            false => game,
+        })
+    }
+
+    /// `start_delay` comes before the first question of games without a lobby, `reveal_duration`
+    /// after each answer is revealed.
+    pub fn with_pauses(mut self, start_delay: Duration, reveal_duration: Duration) -> Self {
+        self.start_delay = start_delay;
+        self.reveal_duration = reveal_duration;
+        self
+    }
+
+    /// Only the players who join before the countdown ends can answer.
+    pub fn with_lobby(mut self, countdown: Duration) -> Self {
+        self.lobby_countdown = Some(countdown);
+        self.players = Some(vec![]);
+        self
+    }
+
+    pub fn has_lobby(&self) -> bool {
+        self.players.is_some()
+    }
+
+    pub fn join(&mut self, user_id: UserId) -> Result<(), JoinError> {
+        if self.phase != Phase::Lobby {
+            return Err(JoinError::AlreadyStarted);
+        }
+
+        let Some(players) = &mut self.players else {
+            return Err(JoinError::NoLobby);
+        };
+
+        if players.contains(&user_id) {
+            return Err(JoinError::AlreadyJoined);
+        }
+
+        players.push(user_id);
+
+        Ok(())
+    }
+
+    /// Players who joined the lobby, `None` for games without a lobby.
+    pub fn players(&self) -> Option<&[UserId]> {
+        self.players.as_deref()
+    }
+
+    /// How long the current phase lasts, unless the game is stopped.
+    pub fn phase_duration(&self) -> Duration {
+        match self.phase {
+            Phase::Lobby => self.lobby_countdown.unwrap_or(self.start_delay),
+            Phase::Question { .. } => self.answer_window,
+            Phase::Reveal { .. } => self.reveal_duration,
+            Phase::Finished => Duration::ZERO,
+        }
+    }
+
+    /// Moves to the next phase. A stopped game still reveals the question being asked, then
+    /// finishes.
+    pub fn advance(&mut self, now: DateTime<FixedOffset>) -> Phase {
+        let next_index = match self.phase {
+            Phase::Lobby => Some(0),
+            Phase::Question { index, .. } => {
+                self.phase = Phase::Reveal { index };
+                return self.phase;
+            }
+            Phase::Reveal { index } => Some(index + 1),
+            Phase::Finished => None,
+        };
+
+        self.answers.clear();
+        self.hints_shown = 0;
+        self.phase = match next_index {
+            Some(index) if !self.stopped && index < self.questions.len() => Phase::Question {
+                index,
+                asked_at: now,
+            },
+            _ => Phase::Finished,
+        };
+
+        self.phase
+    }
+
+    /// Ends the game after the current question.
+    pub fn stop(&mut self) {
+        self.stopped = true;
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.stopped
+    }
+
+    pub fn submit_answer(
+        &mut self,
+        user_id: UserId,
+        guess: Option<i64>,
+        answered_at: DateTime<FixedOffset>,
+    ) -> Result<&Answer, AnswerError> {
+        let Phase::Question { index, asked_at } = self.phase else {
+            return Err(AnswerError::NotAcceptingAnswers);
+        };
+
+        if !self.is_player(user_id) {
+            return Err(AnswerError::NotJoined);
+        }
+
+        if self.answers.iter().any(|answer| answer.user_id == user_id) {
+            return Err(AnswerError::AlreadyAnswered);
+        }
+
+        let question = &self.questions[index];
+        let response_time = answered_at.signed_duration_since(asked_at);
+        let (correct, points) = match (guess, question.answer) {
+            (Some(guess), Some(answer)) => (
+                guess == answer,
+                score::close_answer_points(
+                    (guess - answer).abs(),
+                    question.kind.tolerance(),
+                    response_time,
+                    self.answer_window,
+                    self.hints_shown,
+                ),
+            ),
+            _ => (
+                false,
+                score::answer_points(false, response_time, self.answer_window, self.hints_shown),
+            ),
+        };
+
+        self.scoreboard
+            .record(user_id, index, correct, response_time, points);
+
+        self.answers.push(Answer {
+            user_id,
+            guess,
+            correct,
+            response_time,
+            points,
+        });
+
+        Ok(self.answers.last().unwrap())
+    }
+
+    /// Whether the user can answer, anyone can in games without a lobby.
+    pub fn is_player(&self, user_id: UserId) -> bool {
+        self.players
+            .as_ref()
+            .is_none_or(|players| players.contains(&user_id))
+    }
+
+    /// Shows the next hint of the question being asked, which lowers the points of the answers
+    /// given from now on. Returns `None` when there is no hint left.
+    pub fn reveal_hint(&mut self) -> Option<&str> {
+        let Phase::Question { index, .. } = self.phase else {
+            return None;
+        };
+
+        let hint = self.questions.get(index)?.hints.get(self.hints_shown)?;
+        self.hints_shown += 1;
+
+        Some(hint)
+    }
+
+    pub fn has_hint_left(&self) -> bool {
+        match self.phase {
+            Phase::Question { index, .. } => self
+                .questions
+                .get(index)
+                .is_some_and(|question| self.hints_shown < question.hints.len()),
+            _ => false,
+        }
+    }
+
+    /// Hints shown on the question being asked or revealed.
+    pub fn shown_hints(&self) -> &[String] {
+        self.current_question()
+            .map_or(&[], |question| &question.hints[..self.hints_shown])
     }
 
-    pub fn messages(&self) -> &Vec<message::Model> {
-        &self.quotes
+    /// Question being asked or revealed.
+    pub fn current_question(&self) -> Option<&Question> {
+        self.current_index()
+            .and_then(|index| self.questions.get(index))
     }
 
-    pub fn users(&self) -> &Vec<UserWithEmoji> {
-        &self.users
+    fn current_index(&self) -> Option<usize> {
+        match self.phase {
+            Phase::Question { index, .. } | Phase::Reveal { index } => Some(index),
+            Phase::Lobby | Phase::Finished => None,
+        }
+    }
+
+    /// Possible responses of the question being asked or revealed.
+    pub fn current_choices(&self) -> &[Choice] {
+        self.current_question()
+            .map_or(&[], |question| &question.choices)
+    }
+
+    /// Answers to the question being asked or revealed.
+    pub fn answers(&self) -> &[Answer] {
+        &self.answers
+    }
+
+    /// Correct answers to the current question, fastest first.
+    pub fn correct_answers(&self) -> Vec<&Answer> {
+        self.answers
+            .iter()
+            .filter(|answer| answer.correct)
+            .sorted_by_key(|answer| answer.response_time)
+            .collect()
+    }
+
+    /// Wrong answers to the current question which still earned points for being close, best
+    /// first.
+    pub fn close_answers(&self) -> Vec<&Answer> {
+        self.answers
+            .iter()
+            .filter(|answer| !answer.correct && answer.points > 0)
+            .sorted_by_key(|answer| -answer.points)
+            .collect()
+    }
+
+    /// Wrong guesses to the current question and how many players made them, most common first.
+    pub fn wrong_guesses(&self) -> Vec<(Option<i64>, usize)> {
+        self.answers
+            .iter()
+            .filter(|answer| !answer.correct)
+            .map(|answer| answer.guess)
+            .counts()
+            .into_iter()
+            .sorted_by(|(_, a), (_, b)| b.cmp(a))
+            .collect()
+    }
+
+    pub fn questions(&self) -> &[Question] {
+        &self.questions
+    }
+
+    /// Label of a possible response of the current question.
+    pub fn choice_label(&self, id: Option<i64>) -> Option<&str> {
+        self.current_question()?
+            .choice(id)
+            .map(|choice| choice.label.as_str())
+    }
+
+    pub fn scoreboard(&self) -> &Scoreboard {
+        &self.scoreboard
+    }
+}
+
+/// One of `kinds` at random, [`QuestionKind::Author`] when there is none.
+fn pick_kind(kinds: &[QuestionKind]) -> QuestionKind {
+    kinds
+        .choose(&mut rand::thread_rng())
+        .copied()
+        .unwrap_or(QuestionKind::Author)
+}
+
+/// Possible responses of a question about who said `quote`: `choices_per_question` of the users,
+/// or all of them, without going over `max_choices`.
+async fn pick_users<'a>(
+    database: &Database,
+    quote: &message::Model,
+    users: &'a [UserWithEmoji],
+    options: &GameOptions,
+    max_choices: usize,
+) -> Result<Vec<&'a UserWithEmoji>, DatabaseError> {
+    let n_choices = options
+        .choices_per_question
+        .map_or(users.len(), |choices_per_question| {
+            choices_per_question as usize
+        })
+        .min(max_choices);
+
+    if n_choices >= users.len() {
+        return Ok(users.iter().collect());
+    }
+
+    let choices =
+        choices::pick_choices(database, quote, users, n_choices, options.distractors).await?;
+
+    Ok(choices
+        .iter()
+        .filter_map(|id| users.iter().find(|user| user.id == *id))
+        .collect())
+}
+
+/// Emoji given to the possible responses who did not set one, at least as many as the maximum
+/// number of responses.
+const FALLBACK_EMOJI: [&str; 30] = [
+    "🐶", "🐱", "🐭", "🐹", "🐰", "🦊", "🐻", "🐼", "🐨", "🐯", "🦁", "🐮", "🐷", "🐸", "🐵", "🐔",
+    "🐧", "🐦", "🐤", "🦆", "🦉", "🐴", "🦄", "🐝", "🐛", "🦋", "🐌", "🐞", "🐢", "🐙",
+];
+
+/// Gives each user without an emoji one of the fallback emoji no other user has, the same one
+/// from one game to the next as long as it is free.
+fn with_fallback_emoji(mut users: Vec<UserWithEmoji>) -> Vec<UserWithEmoji> {
+    let mut used: HashSet<String> = users.iter().filter_map(|u| u.emoji.clone()).collect();
+
+    for user in users.iter_mut().filter(|u| u.emoji.is_none()) {
+        let start = user.id.unsigned_abs() as usize % FALLBACK_EMOJI.len();
+        let emoji = (0..FALLBACK_EMOJI.len())
+            .map(|offset| FALLBACK_EMOJI[(start + offset) % FALLBACK_EMOJI.len()])
+            .find(|emoji| !used.contains(*emoji));
+
+        if let Some(emoji) = emoji {
+            used.insert(emoji.to_string());
+            user.emoji = Some(emoji.to_string());
+        }
+    }
+
+    users
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Duration = Duration::from_secs(10);
+
+    fn at(seconds: i64) -> DateTime<FixedOffset> {
+        DateTime::from_timestamp(1_700_000_000 + seconds, 0)
+            .unwrap()
+            .fixed_offset()
+    }
+
+    fn question(hints: &[&str]) -> Question {
+        let quote = message::Model {
+            id: 1,
+            channel_id: 1,
+            author_id: Some(1),
+            content: "quote".to_string(),
+            timestamp: at(0),
+            edited_at: None,
+            deleted_at: None,
+        };
+        let users: Vec<UserWithEmoji> = (1..=3)
+            .map(|id| UserWithEmoji {
+                id,
+                name: format!("user {id}"),
+                emoji: None,
+            })
+            .collect();
+
+        Question::author(quote, &users.iter().collect::<Vec<_>>())
+            .with_hints(hints.iter().map(|hint| hint.to_string()).collect())
+    }
+
+    fn game(n_questions: usize) -> Game {
+        Game::new((0..n_questions).map(|_| question(&[])).collect(), WINDOW)
+    }
+
+    fn user(id: u64) -> UserId {
+        UserId::new(id)
+    }
+
+    #[test]
+    fn advance_asks_then_reveals_every_question() {
+        let mut game = game(2);
+
+        assert_eq!(game.phase, Phase::Lobby);
+        assert_eq!(
+            game.advance(at(0)),
+            Phase::Question {
+                index: 0,
+                asked_at: at(0)
+            }
+        );
+        assert_eq!(game.advance(at(10)), Phase::Reveal { index: 0 });
+        assert_eq!(
+            game.advance(at(15)),
+            Phase::Question {
+                index: 1,
+                asked_at: at(15)
+            }
+        );
+        assert_eq!(game.advance(at(25)), Phase::Reveal { index: 1 });
+        assert_eq!(game.advance(at(30)), Phase::Finished);
+        assert_eq!(game.advance(at(35)), Phase::Finished);
+    }
+
+    #[test]
+    fn stopped_game_reveals_the_current_question_then_finishes() {
+        let mut game = game(3);
+        game.advance(at(0));
+
+        game.stop();
+
+        assert!(game.is_stopped());
+        assert_eq!(game.advance(at(5)), Phase::Reveal { index: 0 });
+        assert_eq!(game.advance(at(10)), Phase::Finished);
+    }
+
+    #[test]
+    fn stopped_lobby_finishes_without_questions() {
+        let mut game = game(3);
+
+        game.stop();
+
+        assert_eq!(game.advance(at(0)), Phase::Finished);
+    }
+
+    #[test]
+    fn first_answer_is_locked_in() {
+        let mut game = game(1);
+        game.advance(at(0));
+
+        let answer = game.submit_answer(user(1), Some(1), at(2)).unwrap();
+        assert!(answer.correct);
+
+        assert!(matches!(
+            game.submit_answer(user(1), Some(2), at(3)),
+            Err(AnswerError::AlreadyAnswered)
+        ));
+        assert_eq!(game.answers().len(), 1);
+        assert_eq!(game.answers()[0].guess, Some(1));
+    }
+
+    #[test]
+    fn only_players_who_joined_can_answer_in_lobby_games() {
+        let mut game = game(1).with_lobby(Duration::from_secs(30));
+        game.join(user(1)).unwrap();
+        assert!(matches!(game.join(user(1)), Err(JoinError::AlreadyJoined)));
+        game.advance(at(0));
+
+        assert!(matches!(game.join(user(2)), Err(JoinError::AlreadyStarted)));
+        assert!(matches!(
+            game.submit_answer(user(2), Some(1), at(1)),
+            Err(AnswerError::NotJoined)
+        ));
+        assert!(game.submit_answer(user(1), Some(1), at(1)).is_ok());
+    }
+
+    #[test]
+    fn anyone_can_answer_without_lobby() {
+        let mut game = game(1);
+
+        assert!(matches!(game.join(user(1)), Err(JoinError::NoLobby)));
+        game.advance(at(0));
+        assert!(game.submit_answer(user(42), Some(2), at(1)).is_ok());
+    }
+
+    #[test]
+    fn answers_are_only_accepted_during_a_question() {
+        let mut game = game(1);
+
+        assert!(matches!(
+            game.submit_answer(user(1), Some(1), at(0)),
+            Err(AnswerError::NotAcceptingAnswers)
+        ));
+
+        game.advance(at(0));
+        game.advance(at(10));
+
+        assert!(matches!(
+            game.submit_answer(user(1), Some(1), at(11)),
+            Err(AnswerError::NotAcceptingAnswers)
+        ));
+    }
+
+    #[test]
+    fn hints_are_revealed_in_order_until_none_is_left() {
+        let mut game = Game::new(vec![question(&["first", "second"]), question(&[])], WINDOW);
+
+        assert!(!game.has_hint_left());
+        assert_eq!(game.reveal_hint(), None);
+
+        game.advance(at(0));
+
+        assert!(game.has_hint_left());
+        assert_eq!(game.reveal_hint(), Some("first"));
+        assert!(game.has_hint_left());
+        assert_eq!(game.reveal_hint(), Some("second"));
+        assert!(!game.has_hint_left());
+        assert_eq!(game.reveal_hint(), None);
+        assert_eq!(game.shown_hints(), ["first", "second"]);
+
+        game.advance(at(10));
+        assert!(!game.has_hint_left());
+        assert_eq!(game.reveal_hint(), None);
+
+        game.advance(at(15));
+        assert!(game.shown_hints().is_empty());
+    }
+
+    #[test]
+    fn hints_lower_the_points_of_later_answers() {
+        let mut game = Game::new(vec![question(&["hint"])], WINDOW);
+        game.advance(at(0));
+
+        let before_hint = game.submit_answer(user(1), Some(1), at(0)).unwrap().points;
+        game.reveal_hint();
+        let after_hint = game.submit_answer(user(2), Some(1), at(0)).unwrap().points;
+
+        assert_eq!(before_hint - after_hint, score::HINT_PENALTY);
     }
 }
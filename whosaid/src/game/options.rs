@@ -0,0 +1,301 @@
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use chrono::{DateTime, Days, FixedOffset, NaiveDate};
+use serenity::all::ChannelId;
+
+use crate::database::guild_config::GuildConfig;
+use crate::game::question::QuestionKind;
+use crate::game::{LOBBY_DURATION, REVEAL_DURATION};
+
+pub const QUESTIONS_RANGE: RangeInclusive<u32> = 1..=50;
+pub const MINIMUM_QUOTE_LENGTH_RANGE: RangeInclusive<u32> = 1..=2000;
+/// A message holds at most 25 buttons.
+pub const CHOICES_RANGE: RangeInclusive<u32> = 2..=25;
+pub const CHOICES_PER_QUESTION_RANGE: RangeInclusive<u32> = 2..=25;
+pub const ANSWER_TIMEOUT_SECS_RANGE: RangeInclusive<u32> = 5..=120;
+pub const HINT_AFTER_SECS_RANGE: RangeInclusive<u32> = 3..=60;
+pub const LOBBY_COUNTDOWN_SECS_RANGE: RangeInclusive<u32> = 10..=300;
+pub const PAUSE_SECS_RANGE: RangeInclusive<u32> = 1..=60;
+
+#[derive(Debug, thiserror::Error)]
+pub enum GameOptionError {
+    #[error("`{name}` must be between {} and {}, got {value}", range.start(), range.end())]
+    OutOfRange {
+        name: &'static str,
+        range: RangeInclusive<u32>,
+        value: i64,
+    },
+    #[error("`{name}` must be a whole number, got `{value}`")]
+    NotANumber { name: &'static str, value: String },
+    #[error("`{name}` must be yes or no, got `{value}`")]
+    NotABoolean { name: &'static str, value: String },
+    #[error("`{name}` must be a date formatted as YYYY-MM-DD, got `{value}`")]
+    InvalidDate { name: &'static str, value: String },
+    #[error("`from` ({from}) must not be after `to` ({to})")]
+    EmptyPeriod { from: NaiveDate, to: NaiveDate },
+}
+
+/// What to do with the possible responses who did not set an emoji with `/emoji`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MissingEmoji {
+    /// Give them a generated emoji.
+    Fallback,
+    /// Leave them out of the game.
+    Exclude,
+}
+
+/// How the wrong possible responses of a question are picked.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum DistractorStrategy {
+    Random,
+    /// Users whose activity in the channel of the quote is close to the author's.
+    SameChannel,
+    /// Users who often answer the author in the channel of the quote.
+    Conversation,
+}
+
+pub struct GameOptions {
+    pub n_questions: u32,
+    /// Each question asks about one of these, picked at random.
+    pub question_kinds: Vec<QuestionKind>,
+    pub minimum_quote_length: u32,
+    pub n_choices: u32,
+    /// Only show the author and some of the other possible responses on each question.
+    pub choices_per_question: Option<u32>,
+    pub distractors: DistractorStrategy,
+    /// Only quote messages from this channel and its threads.
+    pub channel_id: Option<ChannelId>,
+    /// Only quote messages from these channels and their threads, any channel when empty.
+    pub allowed_channels: Vec<ChannelId>,
+    /// Never quote messages from these channels and their threads.
+    pub denied_channels: Vec<ChannelId>,
+    /// First day, included, quotes can be from.
+    pub from: Option<NaiveDate>,
+    /// Last day, included, quotes can be from.
+    pub to: Option<NaiveDate>,
+    pub answer_timeout: Duration,
+    /// Let players ask for hints about the quotes.
+    pub hints: bool,
+    /// Show a hint on its own every time this much time passes without one.
+    pub hint_after: Option<Duration>,
+    pub include_former_members: bool,
+    pub missing_emoji: MissingEmoji,
+    /// Let players join before the game starts, only joined players can answer.
+    pub lobby: bool,
+    pub lobby_countdown: Duration,
+    /// Time between the start announcement and the first question, for games without a lobby.
+    pub start_delay: Duration,
+    /// Time the answer of a question stays on screen before the next question.
+    pub reveal_duration: Duration,
+}
+
+impl Default for GameOptions {
+    fn default() -> Self {
+        Self {
+            n_questions: 10,
+            question_kinds: vec![QuestionKind::Author],
+            minimum_quote_length: 10,
+            n_choices: 13,
+            choices_per_question: None,
+            distractors: DistractorStrategy::Random,
+            channel_id: None,
+            allowed_channels: vec![],
+            denied_channels: vec![],
+            from: None,
+            to: None,
+            answer_timeout: Duration::from_secs(15),
+            hints: true,
+            hint_after: None,
+            include_former_members: true,
+            missing_emoji: MissingEmoji::Fallback,
+            lobby: false,
+            lobby_countdown: Duration::from_secs(60),
+            start_delay: LOBBY_DURATION,
+            reveal_duration: REVEAL_DURATION,
+        }
+    }
+}
+
+impl GameOptions {
+    /// The defaults of a guild, the options of a game are read over them.
+    pub fn from_config(config: GuildConfig) -> Self {
+        Self {
+            n_questions: config.n_questions,
+            minimum_quote_length: config.minimum_quote_length,
+            n_choices: config.n_choices,
+            answer_timeout: config.answer_timeout,
+            start_delay: config.start_delay,
+            reveal_duration: config.reveal_duration,
+            allowed_channels: config.allowed_channels,
+            denied_channels: config.denied_channels,
+            ..Default::default()
+        }
+    }
+
+    /// Start of the `from` day, in UTC.
+    pub fn sent_after(&self) -> Option<DateTime<FixedOffset>> {
+        self.from.map(start_of_day)
+    }
+
+    /// Start of the day following the `to` day, in UTC.
+    pub fn sent_before(&self) -> Option<DateTime<FixedOffset>> {
+        self.to
+            .map(|to| start_of_day(to.checked_add_days(Days::new(1)).unwrap_or(to)))
+    }
+}
+
+fn start_of_day(date: NaiveDate) -> DateTime<FixedOffset> {
+    date.and_time(Default::default()).and_utc().fixed_offset()
+}
+
+pub fn check_range(
+    name: &'static str,
+    value: i64,
+    range: RangeInclusive<u32>,
+) -> Result<u32, GameOptionError> {
+    match u32::try_from(value) {
+        Ok(value) if range.contains(&value) => Ok(value),
+        _ => Err(GameOptionError::OutOfRange { name, range, value }),
+    }
+}
+
+pub fn parse_number(
+    name: &'static str,
+    input: &str,
+    range: RangeInclusive<u32>,
+) -> Result<u32, GameOptionError> {
+    let value = input
+        .trim()
+        .parse::<i64>()
+        .map_err(|_| GameOptionError::NotANumber {
+            name,
+            value: input.to_string(),
+        })?;
+
+    check_range(name, value, range)
+}
+
+pub fn parse_yes_no(name: &'static str, input: &str) -> Result<bool, GameOptionError> {
+    match input.trim().to_lowercase().as_str() {
+        "yes" | "y" | "true" => Ok(true),
+        "no" | "n" | "false" => Ok(false),
+        _ => Err(GameOptionError::NotABoolean {
+            name,
+            value: input.to_string(),
+        }),
+    }
+}
+
+pub fn parse_date(name: &'static str, input: &str) -> Result<NaiveDate, GameOptionError> {
+    NaiveDate::parse_from_str(input.trim(), "%Y-%m-%d").map_err(|_| GameOptionError::InvalidDate {
+        name,
+        value: input.to_string(),
+    })
+}
+
+pub fn check_period(from: Option<NaiveDate>, to: Option<NaiveDate>) -> Result<(), GameOptionError> {
+    match (from, to) {
+        (Some(from), Some(to)) if from > to => Err(GameOptionError::EmptyPeriod { from, to }),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn numbers_within_the_range_are_accepted() {
+        assert_eq!(
+            parse_number("questions", "10", QUESTIONS_RANGE).unwrap(),
+            10
+        );
+        assert_eq!(
+            parse_number("questions", " 1 ", QUESTIONS_RANGE).unwrap(),
+            1
+        );
+        assert_eq!(
+            parse_number("questions", "50", QUESTIONS_RANGE).unwrap(),
+            50
+        );
+    }
+
+    #[test]
+    fn numbers_out_of_the_range_are_rejected() {
+        assert!(matches!(
+            parse_number("questions", "51", QUESTIONS_RANGE),
+            Err(GameOptionError::OutOfRange { value: 51, .. })
+        ));
+        assert!(matches!(
+            parse_number("questions", "-1", QUESTIONS_RANGE),
+            Err(GameOptionError::OutOfRange { value: -1, .. })
+        ));
+        assert!(matches!(
+            check_range("questions", i64::MAX, QUESTIONS_RANGE),
+            Err(GameOptionError::OutOfRange { .. })
+        ));
+    }
+
+    #[test]
+    fn other_text_is_not_a_number() {
+        for input in ["", "ten", "1.5", "1e3"] {
+            assert!(matches!(
+                parse_number("questions", input, QUESTIONS_RANGE),
+                Err(GameOptionError::NotANumber { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn dates_are_parsed_as_iso_dates() {
+        assert_eq!(parse_date("from", "2024-02-29").unwrap(), date(2024, 2, 29));
+        assert_eq!(
+            parse_date("from", " 2024-01-05 ").unwrap(),
+            date(2024, 1, 5)
+        );
+
+        for input in ["", "2023-02-29", "05/01/2024", "2024-1"] {
+            assert!(matches!(
+                parse_date("from", input),
+                Err(GameOptionError::InvalidDate { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn periods_must_not_end_before_they_start() {
+        assert!(check_period(Some(date(2024, 1, 1)), Some(date(2024, 1, 1))).is_ok());
+        assert!(check_period(Some(date(2024, 1, 1)), Some(date(2024, 6, 1))).is_ok());
+        assert!(check_period(Some(date(2024, 6, 1)), None).is_ok());
+        assert!(check_period(None, Some(date(2024, 6, 1))).is_ok());
+        assert!(matches!(
+            check_period(Some(date(2024, 6, 1)), Some(date(2024, 1, 1))),
+            Err(GameOptionError::EmptyPeriod { .. })
+        ));
+    }
+
+    #[test]
+    fn yes_and_no_are_parsed_whatever_their_case() {
+        for input in ["yes", "Y", " true ", "YES"] {
+            assert!(parse_yes_no("hints", input).unwrap());
+        }
+        for input in ["no", "N", "false", " No "] {
+            assert!(!parse_yes_no("hints", input).unwrap());
+        }
+    }
+
+    #[test]
+    fn other_answers_are_not_yes_or_no() {
+        for input in ["", "maybe", "1", "yess"] {
+            assert!(matches!(
+                parse_yes_no("hints", input),
+                Err(GameOptionError::NotABoolean { .. })
+            ));
+        }
+    }
+}
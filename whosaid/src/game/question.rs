@@ -0,0 +1,465 @@
+use std::ops::RangeInclusive;
+
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate};
+use itertools::Itertools;
+use rand::Rng;
+use rand::seq::{IteratorRandom, SliceRandom};
+
+use entity::{channel, message};
+
+use crate::database::messages::MessagePeriod;
+use crate::database::user::UserWithEmoji;
+
+/// Users offered on real or fake questions, each one gets a real and a fake button.
+pub const MAX_REAL_OR_FAKE_USERS: usize = 10;
+const FAKE_EMOJI: &str = "🤖";
+/// Quotes shorter than this are too short to be cut in half.
+pub const FINISH_SENTENCE_MIN_LENGTH: u32 = 60;
+/// Endings offered besides the real one.
+pub const FINISH_SENTENCE_WRONG_ENDINGS: u64 = 3;
+const ENDING_LABELS: [&str; 4] = ["A", "B", "C", "D"];
+
+/// What players are asked about a quote.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuestionKind {
+    /// Who said the quote.
+    Author,
+    /// Which channel the quote was said in.
+    Channel,
+    /// Which year the quote was said in, close guesses earn some points.
+    Year,
+    /// Which month the quote was said in, close guesses earn some points.
+    Month,
+    /// Whether the quote was said or generated in the style of a user, and by whom.
+    RealOrFake,
+    /// How the quote ends, among endings of other messages.
+    FinishSentence,
+}
+
+impl QuestionKind {
+    pub const ALL: [QuestionKind; 6] = [
+        QuestionKind::Author,
+        QuestionKind::Channel,
+        QuestionKind::Year,
+        QuestionKind::Month,
+        QuestionKind::RealOrFake,
+        QuestionKind::FinishSentence,
+    ];
+
+    /// Name stored with the questions of past games.
+    pub fn name(self) -> &'static str {
+        match self {
+            QuestionKind::Author => "author",
+            QuestionKind::Channel => "channel",
+            QuestionKind::Year => "year",
+            QuestionKind::Month => "month",
+            QuestionKind::RealOrFake => "real_or_fake",
+            QuestionKind::FinishSentence => "finish_sentence",
+        }
+    }
+
+    pub fn prompt(self) -> &'static str {
+        match self {
+            QuestionKind::Author => "Who said this ?",
+            QuestionKind::Channel => "Where was this said ?",
+            QuestionKind::Year => "What year was this said ?",
+            QuestionKind::Month => "What month was this said ?",
+            QuestionKind::RealOrFake => "Who said this, or is it a fake ?",
+            QuestionKind::FinishSentence => "How does this end ?",
+        }
+    }
+
+    /// How far from the answer a guess can be and still earn points, 0 when only the answer
+    /// does.
+    pub fn tolerance(self) -> i64 {
+        match self {
+            QuestionKind::Author
+            | QuestionKind::Channel
+            | QuestionKind::RealOrFake
+            | QuestionKind::FinishSentence => 0,
+            QuestionKind::Year => 2,
+            QuestionKind::Month => 6,
+        }
+    }
+}
+
+/// A possible response to a question, `id` is the id of a user or of a channel, a year, a month
+/// counted from year 0, or the position of an ending. The id of a user is negated for a fake quote
+/// in their style.
+pub struct Choice {
+    pub id: i64,
+    pub label: String,
+    pub emoji: Option<String>,
+    /// Text of the choice shown with the question, when it is too long for a button.
+    pub description: Option<String>,
+}
+
+pub struct Question {
+    pub kind: QuestionKind,
+    pub quote: message::Model,
+    /// The content of the quote was generated, the rest of it comes from the message it imitates.
+    pub generated: bool,
+    /// Id of the right choice.
+    pub answer: Option<i64>,
+    /// Possible responses, in the order they are shown.
+    pub choices: Vec<Choice>,
+    /// Hints from the vaguest to the most telling.
+    pub hints: Vec<String>,
+}
+
+impl Question {
+    /// Asks who said the quote among `users`.
+    pub fn author(quote: message::Model, users: &[&UserWithEmoji]) -> Self {
+        let choices = users
+            .iter()
+            .map(|user| Choice {
+                id: user.id,
+                label: user.name.clone(),
+                emoji: user.emoji.clone(),
+                description: None,
+            })
+            .collect();
+
+        Self::new(QuestionKind::Author, quote.author_id, quote, choices)
+    }
+
+    /// Asks whether the quote is real or a fake in the style of its author, and who said it or is
+    /// imitated among `users`. `fake` replaces the content of the quote when given.
+    pub fn real_or_fake(
+        mut quote: message::Model,
+        users: &[&UserWithEmoji],
+        fake: Option<String>,
+    ) -> Self {
+        let generated = fake.is_some();
+        if let Some(fake) = fake {
+            quote.content = fake;
+        }
+
+        let answer = quote.author_id.map(|id| if generated { -id } else { id });
+
+        let real_choices = users.iter().map(|user| Choice {
+            id: user.id,
+            label: user.name.clone(),
+            emoji: user.emoji.clone(),
+            description: None,
+        });
+        let fake_choices = users.iter().map(|user| Choice {
+            id: -user.id,
+            label: format!("Fake {}", user.name),
+            emoji: Some(FAKE_EMOJI.to_string()),
+            description: None,
+        });
+
+        Self {
+            generated,
+            ..Self::new(
+                QuestionKind::RealOrFake,
+                answer,
+                quote,
+                real_choices.chain(fake_choices).collect(),
+            )
+        }
+    }
+
+    /// Asks how the quote ends, among its real ending and the endings of `other_messages`. The
+    /// quote is cut in half. `None` when it is too short or none of the other endings differ.
+    pub fn finish_sentence(mut quote: message::Model, other_messages: &[String]) -> Option<Self> {
+        let (beginning, ending) = split_in_half(&quote.content)?;
+
+        let mut endings: Vec<String> = other_messages
+            .iter()
+            .filter_map(|message| split_in_half(message).map(|(_, ending)| ending))
+            .filter(|other| *other != ending)
+            .unique()
+            .take(ENDING_LABELS.len() - 1)
+            .collect();
+
+        if endings.is_empty() {
+            return None;
+        }
+
+        endings.push(ending.clone());
+        endings.shuffle(&mut rand::thread_rng());
+
+        let answer = endings.iter().position(|other| *other == ending)? as i64;
+        let choices = endings
+            .into_iter()
+            .zip(ENDING_LABELS)
+            .enumerate()
+            .map(|(i, (ending, label))| Choice {
+                id: i as i64,
+                label: label.to_string(),
+                emoji: None,
+                description: Some(format!("…{ending}")),
+            })
+            .collect();
+
+        quote.content = format!("{beginning}…");
+
+        Some(Self::new(
+            QuestionKind::FinishSentence,
+            Some(answer),
+            quote,
+            choices,
+        ))
+    }
+
+    /// Asks which of the channels of the guild the quote was said in, quotes from threads were
+    /// said in the channel of the thread. `None` when the channel of the quote is unknown or is
+    /// the only one.
+    pub fn channel(
+        quote: message::Model,
+        channels: &[channel::Model],
+        n_choices: usize,
+    ) -> Option<Self> {
+        let channel = channels
+            .iter()
+            .find(|channel| channel.id == quote.channel_id)?;
+        let answer = channel.parent_id.unwrap_or(channel.id);
+
+        let mut choices: Vec<&channel::Model> = channels
+            .iter()
+            .filter(|channel| channel.parent_id.is_none() && channel.id != answer)
+            .choose_multiple(&mut rand::thread_rng(), n_choices.saturating_sub(1));
+
+        if choices.is_empty() {
+            return None;
+        }
+
+        choices.push(channels.iter().find(|channel| channel.id == answer)?);
+        choices.shuffle(&mut rand::thread_rng());
+
+        let choices = choices
+            .into_iter()
+            .map(|channel| Choice {
+                id: channel.id,
+                label: format!("#{}", channel.name),
+                emoji: None,
+                description: None,
+            })
+            .collect();
+
+        Some(Self::new(
+            QuestionKind::Channel,
+            Some(answer),
+            quote,
+            choices,
+        ))
+    }
+
+    /// Asks which year the quote was said in, among years of the `period` the guild has messages
+    /// from. `None` when the period spans a single year.
+    pub fn year(quote: message::Model, period: MessagePeriod, n_choices: usize) -> Option<Self> {
+        let answer = quote.timestamp.year() as i64;
+        let years = window(
+            answer,
+            period.0.year() as i64..=period.1.year() as i64,
+            n_choices,
+        );
+
+        if years.start() == years.end() {
+            return None;
+        }
+
+        let choices = years
+            .map(|year| Choice {
+                id: year,
+                label: year.to_string(),
+                emoji: None,
+                description: None,
+            })
+            .collect();
+
+        Some(Self::new(QuestionKind::Year, Some(answer), quote, choices))
+    }
+
+    /// Asks which month the quote was said in, among months of the `period` the guild has
+    /// messages from. `None` when the period spans a single month.
+    pub fn month(quote: message::Model, period: MessagePeriod, n_choices: usize) -> Option<Self> {
+        let answer = month_id(quote.timestamp);
+        let months = window(answer, month_id(period.0)..=month_id(period.1), n_choices);
+
+        if months.start() == months.end() {
+            return None;
+        }
+
+        let choices = months
+            .map(|month| Choice {
+                id: month,
+                label: NaiveDate::from_ymd_opt((month / 12) as i32, (month % 12) as u32 + 1, 1)
+                    .map_or(month.to_string(), |date| date.format("%B %Y").to_string()),
+                emoji: None,
+                description: None,
+            })
+            .collect();
+
+        Some(Self::new(QuestionKind::Month, Some(answer), quote, choices))
+    }
+
+    fn new(
+        kind: QuestionKind,
+        answer: Option<i64>,
+        quote: message::Model,
+        choices: Vec<Choice>,
+    ) -> Self {
+        Self {
+            kind,
+            quote,
+            generated: false,
+            answer,
+            choices,
+            hints: vec![],
+        }
+    }
+
+    /// `hints` goes from the vaguest to the most telling.
+    pub fn with_hints(mut self, hints: Vec<String>) -> Self {
+        self.hints = hints;
+        self
+    }
+
+    /// Texts of the question shown to the players: its quote, hints and choice descriptions.
+    pub fn texts_mut(&mut self) -> impl Iterator<Item = &mut String> {
+        std::iter::once(&mut self.quote.content)
+            .chain(self.hints.iter_mut())
+            .chain(
+                self.choices
+                    .iter_mut()
+                    .filter_map(|choice| choice.description.as_mut()),
+            )
+    }
+
+    pub fn choice(&self, id: Option<i64>) -> Option<&Choice> {
+        self.choices.iter().find(|choice| id == Some(choice.id))
+    }
+}
+
+/// Splits a message at the word closest to its middle, `None` when it has less than 4 words.
+fn split_in_half(message: &str) -> Option<(String, String)> {
+    let words: Vec<&str> = message.split_whitespace().collect();
+
+    if words.len() < 4 {
+        return None;
+    }
+
+    let middle = message.len() / 2;
+    let mut length = 0;
+    let split = words
+        .iter()
+        .position(|word| {
+            length += word.len() + 1;
+            length >= middle
+        })
+        .map_or(words.len() / 2, |i| i + 1)
+        .clamp(1, words.len() - 1);
+
+    Some((words[..split].join(" "), words[split..].join(" ")))
+}
+
+fn month_id(timestamp: DateTime<FixedOffset>) -> i64 {
+    timestamp.year() as i64 * 12 + timestamp.month0() as i64
+}
+
+/// Up to `size` consecutive values of `range` around `answer`, the answer at a random position so
+/// it is not given away by being in the middle.
+fn window(answer: i64, range: RangeInclusive<i64>, size: usize) -> RangeInclusive<i64> {
+    let range = (*range.start()).min(answer)..=(*range.end()).max(answer);
+    let size = (size as i64).clamp(1, range.end() - range.start() + 1);
+
+    let lowest_start = (answer - size + 1).max(*range.start());
+    let highest_start = answer.min(range.end() - size + 1);
+    let start = rand::thread_rng().gen_range(lowest_start..=highest_start);
+
+    start..=start + size - 1
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn short_messages_are_not_split() {
+        assert_eq!(split_in_half("too short here"), None);
+        assert_eq!(split_in_half("   "), None);
+    }
+
+    #[test]
+    fn messages_are_split_between_words_near_the_middle() {
+        assert_eq!(
+            split_in_half("one two three four"),
+            Some(("one two three".to_string(), "four".to_string()))
+        );
+        assert_eq!(
+            split_in_half("the quick brown fox jumps over the lazy dog"),
+            Some((
+                "the quick brown fox jumps".to_string(),
+                "over the lazy dog".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn both_halves_keep_at_least_a_word() {
+        let (start, end) = split_in_half("supercalifragilisticexpialidocious a b c").unwrap();
+
+        assert_eq!(start, "supercalifragilisticexpialidocious");
+        assert_eq!(end, "a b c");
+
+        let (start, end) = split_in_half("a b c supercalifragilisticexpialidocious").unwrap();
+
+        assert_eq!(start, "a b c");
+        assert_eq!(end, "supercalifragilisticexpialidocious");
+    }
+
+    #[test]
+    fn line_breaks_are_joined_as_spaces() {
+        assert_eq!(
+            split_in_half("one\ntwo  three\tfour five six"),
+            Some(("one two three".to_string(), "four five six".to_string()))
+        );
+    }
+
+    #[test]
+    fn window_contains_the_answer_within_the_range() {
+        for _ in 0..100 {
+            let window = window(2020, 2015..=2025, 4);
+
+            assert!(window.contains(&2020));
+            assert_eq!(window.clone().count(), 4);
+            assert!(*window.start() >= 2015 && *window.end() <= 2025);
+        }
+    }
+
+    #[test]
+    fn window_stops_at_the_ends_of_the_range() {
+        for _ in 0..100 {
+            assert_eq!(window(2015, 2015..=2025, 3), 2015..=2017);
+            assert_eq!(window(2025, 2015..=2025, 3), 2023..=2025);
+        }
+    }
+
+    #[test]
+    fn window_is_smaller_than_a_small_range() {
+        assert_eq!(window(5, 4..=6, 10), 4..=6);
+        assert_eq!(window(5, 5..=5, 4), 5..=5);
+    }
+
+    #[test]
+    fn window_grows_the_range_to_the_answer() {
+        for _ in 0..100 {
+            let window = window(2030, 2015..=2025, 3);
+
+            assert!(window.contains(&2030));
+            assert_eq!(window.clone().count(), 3);
+        }
+    }
+
+    #[test]
+    fn window_puts_the_answer_at_any_position() {
+        let starts: HashSet<i64> = (0..200).map(|_| *window(10, 0..=20, 3).start()).collect();
+
+        assert_eq!(starts, HashSet::from([8, 9, 10]));
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serenity::all::{ChannelId, UserId};
+use tokio::sync::watch;
+
+struct RunningGame {
+    started_by: UserId,
+    stop: watch::Sender<bool>,
+}
+
+/// Games running in each channel, a channel runs at most one game at a time.
+#[derive(Default)]
+pub struct GameRegistry {
+    games: Mutex<HashMap<ChannelId, RunningGame>>,
+}
+
+impl GameRegistry {
+    /// Registers a game in the channel, returns `None` when one is already running there.
+    pub fn start(
+        self: &Arc<Self>,
+        channel_id: ChannelId,
+        started_by: UserId,
+    ) -> Option<RunningGameGuard> {
+        let mut games = self.games.lock().unwrap();
+
+        if games.contains_key(&channel_id) {
+            return None;
+        }
+
+        let (stop, stopped) = watch::channel(false);
+        games.insert(channel_id, RunningGame { started_by, stop });
+
+        Some(RunningGameGuard {
+            registry: self.clone(),
+            channel_id,
+            stopped,
+        })
+    }
+
+    pub fn started_by(&self, channel_id: ChannelId) -> Option<UserId> {
+        self.games
+            .lock()
+            .unwrap()
+            .get(&channel_id)
+            .map(|game| game.started_by)
+    }
+
+    /// Asks the game running in the channel to stop, returns whether there was one.
+    pub fn stop(&self, channel_id: ChannelId) -> bool {
+        match self.games.lock().unwrap().get(&channel_id) {
+            Some(game) => {
+                game.stop.send_replace(true);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Held by a running game, frees its channel when dropped.
+pub struct RunningGameGuard {
+    registry: Arc<GameRegistry>,
+    channel_id: ChannelId,
+    stopped: watch::Receiver<bool>,
+}
+
+impl RunningGameGuard {
+    pub fn is_stopped(&self) -> bool {
+        *self.stopped.borrow()
+    }
+
+    /// Resolves once the game is asked to stop.
+    pub async fn stopped(&mut self) {
+        let _ = self.stopped.wait_for(|stopped| *stopped).await;
+    }
+
+    /// Sleeps for `duration`, waking up early if the game is asked to stop.
+    pub async fn sleep(&mut self, duration: Duration) {
+        tokio::select! {
+            _ = tokio::time::sleep(duration) => {}
+            _ = self.stopped() => {}
+        }
+    }
+}
+
+impl Drop for RunningGameGuard {
+    fn drop(&mut self) {
+        self.registry.games.lock().unwrap().remove(&self.channel_id);
+    }
+}
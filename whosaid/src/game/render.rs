@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+/// Characters of a message Discord accepts.
+pub const MAX_MESSAGE_LENGTH: usize = 2000;
+/// Characters of a quote shown in a question, leaving room for the hints and the results.
+pub const MAX_QUOTE_LENGTH: usize = 600;
+/// Characters of the other messages shown in a question, like the endings and the context.
+pub const MAX_EXCERPT_LENGTH: usize = 200;
+/// Characters of the results shown under a revealed question, the question gets the rest.
+pub const MAX_RESULTS_LENGTH: usize = 1000;
+
+enum Mention {
+    User(i64),
+    Role,
+}
+
+/// Replaces the user mentions with the names in `names`, the role mentions with `@role`, and
+/// breaks `@everyone` and `@here` so they cannot ping anyone.
+pub fn neutralise_mentions(text: &str, names: &HashMap<i64, String>) -> String {
+    replace_mentions(text, |mention| match mention {
+        Mention::User(id) => format!("@{}", names.get(&id).map_or("someone", |name| name)),
+        Mention::Role => "@role".to_string(),
+    })
+    .replace("@everyone", "@\u{200B}everyone")
+    .replace("@here", "@\u{200B}here")
+}
+
+/// Ids of the users mentioned as `<@id>` or `<@!id>`.
+pub fn mentioned_user_ids(text: &str) -> Vec<i64> {
+    let mut ids = vec![];
+
+    replace_mentions(text, |mention| {
+        if let Mention::User(id) = mention {
+            ids.push(id);
+        }
+        String::new()
+    });
+
+    ids
+}
+
+/// Quotes every line of `text`, a blockquote otherwise stops at the first line break.
+pub fn block_quote(text: &str) -> String {
+    match text.is_empty() {
+        true => "> ".to_string(),
+        false => text.lines().map(|line| format!("> {line}")).join("\n"),
+    }
+}
+
+/// Cuts `text` to `max_length` characters, ending with an ellipsis when it was cut.
+pub fn truncate(text: &str, max_length: usize) -> String {
+    if text.chars().count() <= max_length {
+        return text.to_string();
+    }
+
+    let mut truncated: String = text.chars().take(max_length.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// Cuts `texts` so they add up to at most `budget` characters. Texts shorter than an even share
+/// of the budget are kept whole, the longer ones share what they leave.
+pub fn truncate_all(texts: &[String], budget: usize) -> Vec<String> {
+    let lengths: Vec<usize> = texts.iter().map(|text| text.chars().count()).collect();
+    let mut limits = vec![0; texts.len()];
+    let mut remaining = budget;
+
+    for (n, i) in (0..texts.len()).sorted_by_key(|&i| lengths[i]).enumerate() {
+        limits[i] = lengths[i].min(remaining / (texts.len() - n));
+        remaining -= limits[i];
+    }
+
+    texts
+        .iter()
+        .zip(limits)
+        .map(|(text, limit)| match limit {
+            0 => String::new(),
+            limit => truncate(text, limit),
+        })
+        .collect()
+}
+
+/// Keeps the first lines of `text` adding up to at most `max_length` characters, ending with an
+/// ellipsis on its own line when some were left out.
+pub fn truncate_lines(text: &str, max_length: usize) -> String {
+    if text.chars().count() <= max_length {
+        return text.to_string();
+    }
+
+    let mut kept = String::new();
+    let mut kept_length = 0;
+
+    for line in text.lines() {
+        let line_length = line.chars().count() + 1;
+
+        // The ellipsis needs a character.
+        if kept_length + line_length + 1 > max_length {
+            break;
+        }
+
+        kept.push_str(line);
+        kept.push('\n');
+        kept_length += line_length;
+    }
+
+    kept.push('…');
+    kept
+}
+
+fn replace_mentions(text: &str, mut replace: impl FnMut(Mention) -> String) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find("<@") {
+        output.push_str(&rest[..start]);
+        rest = &rest[start + 2..];
+
+        match parse_mention(rest) {
+            Some((mention, length)) => {
+                output.push_str(&replace(mention));
+                rest = &rest[length..];
+            }
+            None => output.push_str("<@"),
+        }
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// Parses the end of a mention, following its `<@`, returns it and its length.
+fn parse_mention(text: &str) -> Option<(Mention, usize)> {
+    let (role, id_start) = match text.chars().next()? {
+        '!' => (false, 1),
+        '&' => (true, 1),
+        _ => (false, 0),
+    };
+    let end = text.find('>')?;
+    let id = &text[id_start..end];
+
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let mention = match role {
+        true => Mention::Role,
+        false => Mention::User(id.parse().ok()?),
+    };
+
+    Some((mention, end + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names() -> HashMap<i64, String> {
+        HashMap::from([(42, "alice".to_string())])
+    }
+
+    #[test]
+    fn user_mentions_are_replaced_by_names() {
+        assert_eq!(
+            neutralise_mentions("hi <@42> and <@!42>", &names()),
+            "hi @alice and @alice"
+        );
+        assert_eq!(neutralise_mentions("hi <@7>", &names()), "hi @someone");
+    }
+
+    #[test]
+    fn role_and_mass_mentions_cannot_ping() {
+        assert_eq!(neutralise_mentions("<@&123> ping", &names()), "@role ping");
+        assert_eq!(
+            neutralise_mentions("@everyone @here", &names()),
+            "@\u{200B}everyone @\u{200B}here"
+        );
+    }
+
+    #[test]
+    fn broken_mentions_are_kept() {
+        assert_eq!(
+            neutralise_mentions("<@> <@abc> <@12", &names()),
+            "<@> <@abc> <@12"
+        );
+        assert!(mentioned_user_ids("<@abc> <@12").is_empty());
+    }
+
+    #[test]
+    fn mentioned_users_are_listed_in_order() {
+        assert_eq!(mentioned_user_ids("<@1> <@&2> <@!3>"), [1, 3]);
+    }
+
+    #[test]
+    fn short_text_is_not_truncated() {
+        assert_eq!(truncate("hello", 5), "hello");
+        assert_eq!(truncate("", 0), "");
+    }
+
+    #[test]
+    fn long_text_ends_with_an_ellipsis_within_the_limit() {
+        assert_eq!(truncate("hello world", 6), "hello…");
+        assert_eq!(truncate("héllo wörld", 6).chars().count(), 6);
+    }
+
+    #[test]
+    fn short_texts_are_kept_whole_within_the_budget() {
+        let texts = ["a".repeat(10), "b".repeat(100), "c".repeat(300)];
+        let lengths = |texts: Vec<String>| -> Vec<usize> {
+            texts.iter().map(|text| text.chars().count()).collect()
+        };
+
+        assert_eq!(lengths(truncate_all(&texts, 1000)), [10, 100, 300]);
+        assert_eq!(lengths(truncate_all(&texts, 210)), [10, 100, 100]);
+        assert_eq!(lengths(truncate_all(&texts, 90)), [10, 40, 40]);
+        assert_eq!(lengths(truncate_all(&texts, 2)), [0, 1, 1]);
+        assert!(truncate_all(&texts, 90)[1].ends_with('…'));
+        assert!(truncate_all(&[], 10).is_empty());
+    }
+
+    #[test]
+    fn lines_are_kept_whole() {
+        assert_eq!(truncate_lines("one\ntwo", 7), "one\ntwo");
+        assert_eq!(truncate_lines("one\ntwo\nthree", 10), "one\ntwo\n…");
+        assert_eq!(truncate_lines("one\ntwo\nthree", 8), "one\n…");
+        assert_eq!(truncate_lines("a long line", 5), "…");
+    }
+
+    #[test]
+    fn every_line_is_quoted() {
+        assert_eq!(block_quote("one\ntwo"), "> one\n> two");
+        assert_eq!(block_quote(""), "> ");
+    }
+}
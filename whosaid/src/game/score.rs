@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::TimeDelta;
+use itertools::Itertools;
+use serenity::all::UserId;
+
+/// Points awarded for a correct answer, before the speed bonus.
+pub const CORRECT_ANSWER_POINTS: i64 = 100;
+/// Bonus awarded for an instant answer, decreasing linearly to 0 at the end of the answer window.
+pub const MAX_SPEED_BONUS: i64 = 50;
+/// Points removed for a wrong answer.
+pub const WRONG_ANSWER_PENALTY: i64 = 25;
+/// Points removed from a correct answer for each hint shown before it.
+pub const HINT_PENALTY: i64 = 30;
+
+pub fn answer_points(
+    correct: bool,
+    response_time: TimeDelta,
+    answer_window: Duration,
+    hints: usize,
+) -> i64 {
+    if !correct {
+        return -WRONG_ANSWER_PENALTY;
+    }
+
+    let window_ms = answer_window.as_millis() as i64;
+    let remaining_ms = (window_ms - response_time.num_milliseconds()).clamp(0, window_ms);
+    let points = CORRECT_ANSWER_POINTS + MAX_SPEED_BONUS * remaining_ms / window_ms.max(1);
+
+    (points - HINT_PENALTY * hints as i64).max(0)
+}
+
+/// Points of a guess `distance` away from the answer, guesses up to `tolerance` away earn a part
+/// of the points of a correct answer, without the speed bonus.
+pub fn close_answer_points(
+    distance: i64,
+    tolerance: i64,
+    response_time: TimeDelta,
+    answer_window: Duration,
+    hints: usize,
+) -> i64 {
+    if distance == 0 || distance > tolerance {
+        return answer_points(distance == 0, response_time, answer_window, hints);
+    }
+
+    let points = CORRECT_ANSWER_POINTS * (tolerance + 1 - distance) / (tolerance + 1);
+
+    (points - HINT_PENALTY * hints as i64).max(0)
+}
+
+#[derive(Default)]
+pub struct PlayerStats {
+    pub points: i64,
+    pub answers: u32,
+    pub correct_answers: u32,
+    pub longest_streak: u32,
+    pub fastest_answer: Option<TimeDelta>,
+    current_streak: u32,
+    last_correct_question: Option<usize>,
+}
+
+impl PlayerStats {
+    pub fn accuracy(&self) -> f64 {
+        match self.answers {
+            0 => 0.0,
+            answers => self.correct_answers as f64 / answers as f64,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Scoreboard {
+    players: HashMap<UserId, PlayerStats>,
+}
+
+impl Scoreboard {
+    /// Records an answer given to the question at index `question`, which earned `points`.
+    pub fn record(
+        &mut self,
+        user_id: UserId,
+        question: usize,
+        correct: bool,
+        response_time: TimeDelta,
+        points: i64,
+    ) {
+        let stats = self.players.entry(user_id).or_default();
+
+        stats.points += points;
+        stats.answers += 1;
+
+        if correct {
+            stats.correct_answers += 1;
+            stats.current_streak = match stats.last_correct_question {
+                Some(last) if last + 1 == question => stats.current_streak + 1,
+                _ => 1,
+            };
+            stats.longest_streak = stats.longest_streak.max(stats.current_streak);
+            stats.last_correct_question = Some(question);
+            stats.fastest_answer = Some(
+                stats
+                    .fastest_answer
+                    .map_or(response_time, |fastest| fastest.min(response_time)),
+            );
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.players.is_empty()
+    }
+
+    /// Players sorted by points, then by accuracy.
+    pub fn ranking(&self) -> Vec<(&UserId, &PlayerStats)> {
+        self.players
+            .iter()
+            .sorted_by(|(_, a), (_, b)| {
+                b.points
+                    .cmp(&a.points)
+                    .then(b.accuracy().total_cmp(&a.accuracy()))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Duration = Duration::from_secs(10);
+
+    fn seconds(seconds: i64) -> TimeDelta {
+        TimeDelta::seconds(seconds)
+    }
+
+    #[test]
+    fn faster_correct_answers_earn_more_points() {
+        assert_eq!(answer_points(true, seconds(0), WINDOW, 0), 150);
+        assert_eq!(answer_points(true, seconds(5), WINDOW, 0), 125);
+        assert_eq!(answer_points(true, seconds(10), WINDOW, 0), 100);
+        assert_eq!(answer_points(true, seconds(30), WINDOW, 0), 100);
+    }
+
+    #[test]
+    fn wrong_answers_lose_points_whatever_their_speed() {
+        assert_eq!(answer_points(false, seconds(0), WINDOW, 0), -25);
+        assert_eq!(answer_points(false, seconds(10), WINDOW, 2), -25);
+    }
+
+    #[test]
+    fn hints_lower_the_points_down_to_zero() {
+        assert_eq!(answer_points(true, seconds(0), WINDOW, 1), 120);
+        assert_eq!(answer_points(true, seconds(10), WINDOW, 3), 10);
+        assert_eq!(answer_points(true, seconds(10), WINDOW, 4), 0);
+    }
+
+    #[test]
+    fn close_guesses_earn_part_of_the_points() {
+        assert_eq!(close_answer_points(0, 2, seconds(0), WINDOW, 0), 150);
+        assert_eq!(close_answer_points(1, 2, seconds(0), WINDOW, 0), 66);
+        assert_eq!(close_answer_points(2, 2, seconds(0), WINDOW, 0), 33);
+        assert_eq!(close_answer_points(3, 2, seconds(0), WINDOW, 0), -25);
+        assert_eq!(close_answer_points(1, 0, seconds(0), WINDOW, 0), -25);
+    }
+
+    #[test]
+    fn hints_lower_close_guesses_down_to_zero() {
+        assert_eq!(close_answer_points(1, 2, seconds(0), WINDOW, 1), 36);
+        assert_eq!(close_answer_points(2, 2, seconds(0), WINDOW, 2), 0);
+    }
+
+    #[test]
+    fn streaks_count_correct_answers_in_a_row() {
+        let user_id = UserId::new(1);
+        let mut scoreboard = Scoreboard::default();
+
+        scoreboard.record(user_id, 0, true, seconds(4), 130);
+        scoreboard.record(user_id, 1, true, seconds(2), 140);
+        scoreboard.record(user_id, 2, false, seconds(1), -25);
+        scoreboard.record(user_id, 3, true, seconds(3), 135);
+
+        let stats = &scoreboard.players[&user_id];
+        assert_eq!(stats.longest_streak, 2);
+        assert_eq!(stats.current_streak, 1);
+        assert_eq!(stats.points, 380);
+        assert_eq!(stats.answers, 4);
+        assert_eq!(stats.correct_answers, 3);
+        assert_eq!(stats.fastest_answer, Some(seconds(2)));
+    }
+
+    #[test]
+    fn unanswered_questions_break_streaks() {
+        let user_id = UserId::new(1);
+        let mut scoreboard = Scoreboard::default();
+
+        scoreboard.record(user_id, 0, true, seconds(1), 145);
+        scoreboard.record(user_id, 2, true, seconds(1), 145);
+
+        assert_eq!(scoreboard.players[&user_id].longest_streak, 1);
+    }
+
+    #[test]
+    fn ranking_puts_the_most_points_first() {
+        let mut scoreboard = Scoreboard::default();
+
+        scoreboard.record(UserId::new(1), 0, false, seconds(1), -25);
+        scoreboard.record(UserId::new(2), 0, true, seconds(1), 145);
+
+        let ranking: Vec<_> = scoreboard
+            .ranking()
+            .into_iter()
+            .map(|(user_id, _)| *user_id)
+            .collect();
+        assert_eq!(ranking, [UserId::new(2), UserId::new(1)]);
+    }
+}
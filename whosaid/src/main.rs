@@ -1,19 +1,21 @@
 use std::env;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::time::Duration;
 
 use anyhow::Result;
 use dotenv::dotenv;
-use serenity::all::{Command, Interaction};
+use serenity::all::{
+    ChannelId, Command, CommandInteraction, GuildChannel, GuildId, Interaction, Member, Message,
+    MessageId, MessageUpdateEvent, User,
+};
 use serenity::async_trait;
-use serenity::builder::{CreateInteractionResponse, CreateInteractionResponseMessage};
 
 use serenity::model::gateway::Ready;
 use serenity::prelude::*;
-use tracing::{Level, info};
+use tracing::{Level, error, info};
 
+use crate::commands::error::CommandError;
 use crate::database::Database;
+use crate::game::registry::GameRegistry;
 use crate::scrapper::Scrapper;
 
 mod commands;
@@ -23,9 +25,9 @@ mod scrapper;
 mod game;
 
 struct Bot {
-    is_loop_running: AtomicBool,
     database: Arc<Database>,
     scrapper: Arc<Scrapper>,
+    games: Arc<GameRegistry>,
 }
 
 impl Bot {
@@ -33,9 +35,9 @@ impl Bot {
         let database = Arc::new(Database::new().await?);
         let scrapper = Arc::new(Scrapper::new(database.clone()));
         Ok(Self {
-            is_loop_running: AtomicBool::new(false),
             scrapper,
             database,
+            games: Arc::new(GameRegistry::default()),
         })
     }
 }
@@ -48,57 +50,159 @@ impl EventHandler for Bot {
 
         let _guild_command = Command::set_global_commands(
             &ctx.http,
-            vec![commands::whosaid::register(), commands::emoji::register()],
+            vec![
+                commands::whosaid::register(),
+                commands::stop::register(),
+                commands::emoji::register(),
+                commands::filters::register(),
+                commands::config::register(),
+                commands::leaderboard::register(),
+                commands::optout::register(),
+                commands::optin::register(),
+                commands::forget_me::register(),
+            ],
         )
         .await;
 
-        let ctx = Arc::new(ctx);
+        // A new session does not replay the events missed while disconnected, catch up on them.
+        let scrapper = self.scrapper.clone();
+        let scrap_ctx = ctx.clone();
 
-        if !self.is_loop_running.load(Ordering::Relaxed) {
-            let ctx1 = Arc::clone(&ctx);
-            let scrapper = self.scrapper.clone();
+        tokio::spawn(async move {
+            scrapper.scrap(&scrap_ctx).await;
+        });
 
-            tokio::spawn(async move {
-                loop {
-                    scrapper.scrap(&ctx1).await;
-                    tokio::time::sleep(Duration::from_secs(60)).await;
-                }
-            });
+        // Events can also be missed during a session, the channels are caught up regularly.
+        let scrapper = self.scrapper.clone();
+
+        tokio::spawn(async move {
+            scrapper.recover_gaps(&ctx).await;
+        });
+    }
+
+    async fn channel_create(&self, ctx: Context, channel: GuildChannel) {
+        if let Err(e) = self.scrapper.save_live_channel(&ctx, &channel).await {
+            error!("Saving channel {} failed: {}", channel.name, e);
+        }
+    }
+
+    async fn thread_create(&self, ctx: Context, thread: GuildChannel) {
+        if let Err(e) = self.scrapper.save_live_channel(&ctx, &thread).await {
+            error!("Saving thread {} failed: {}", thread.name, e);
+        }
+    }
+
+    async fn guild_member_addition(&self, _ctx: Context, new_member: Member) {
+        if let Err(e) = self.scrapper.save_live_member(&new_member).await {
+            error!("Saving member {} failed: {}", new_member.user.name, e);
+        }
+    }
+
+    async fn guild_member_removal(
+        &self,
+        _ctx: Context,
+        guild_id: GuildId,
+        user: User,
+        _member_data_if_available: Option<Member>,
+    ) {
+        if let Err(e) = self.scrapper.remove_live_member(guild_id, &user).await {
+            error!("Removing member {} failed: {}", user.name, e);
         }
     }
 
     // Set a handler for the `message` event - so that whenever a new message is received - the
     // closure (or function) passed will be called.
-    //
+    async fn message(&self, _ctx: Context, new_message: Message) {
+        if let Err(e) = self.scrapper.save_live_message(&new_message).await {
+            error!("Saving message {} failed: {}", new_message.id, e);
+        }
+    }
+
+    async fn message_update(
+        &self,
+        _ctx: Context,
+        _old_if_available: Option<Message>,
+        _new: Option<Message>,
+        event: MessageUpdateEvent,
+    ) {
+        if let Err(e) = self.scrapper.update_live_message(&event).await {
+            error!("Updating message {} failed: {}", event.id, e);
+        }
+    }
+
+    async fn message_delete(
+        &self,
+        _ctx: Context,
+        _channel_id: ChannelId,
+        deleted_message_id: MessageId,
+        _guild_id: Option<GuildId>,
+    ) {
+        if let Err(e) = self
+            .scrapper
+            .delete_live_messages(&[deleted_message_id])
+            .await
+        {
+            error!("Deleting message {} failed: {}", deleted_message_id, e);
+        }
+    }
+
+    async fn message_delete_bulk(
+        &self,
+        _ctx: Context,
+        channel_id: ChannelId,
+        multiple_deleted_messages_ids: Vec<MessageId>,
+        _guild_id: Option<GuildId>,
+    ) {
+        if let Err(e) = self
+            .scrapper
+            .delete_live_messages(&multiple_deleted_messages_ids)
+            .await
+        {
+            error!(
+                "Deleting messages from channel {} failed: {}",
+                channel_id, e
+            );
+        }
+    }
+
     // Event handlers are dispatched through a threadpool, and so multiple events can be dispatched
     // simultaneously.
     async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
         if let Interaction::Command(command) = interaction {
-            //  println!("Received command interaction: {command:#?}");
-
-            let content = match command.data.name.as_str() {
-                "whosaid" => {
-                    commands::whosaid::run(self.database.clone(), &ctx, &command)
-                        .await
-                        .unwrap();
-                    None
-                }
-                "emoji" => {
-                    commands::emoji::run(self.database.clone(), &ctx, &command)
-                        .await
-                        .unwrap();
-                    None
-                }
-                _ => Some("not implemented :(".to_string()),
-            };
-
-            if let Some(content) = content {
-                let data = CreateInteractionResponseMessage::new().content(content);
-                let builder = CreateInteractionResponse::Message(data);
-                if let Err(why) = command.create_response(&ctx.http, builder).await {
-                    println!("Cannot respond to slash command: {why}");
-                }
+            self.run_command(&ctx, &command).await;
+        }
+    }
+}
+
+impl Bot {
+    #[tracing::instrument(
+        skip_all,
+        fields(
+            command = %command.data.name,
+            user = %command.user.id,
+            guild = ?command.guild_id,
+        )
+    )]
+    async fn run_command(&self, ctx: &Context, command: &CommandInteraction) {
+        let database = self.database.clone();
+
+        let result = match command.data.name.as_str() {
+            "whosaid" => commands::whosaid::run(database, self.games.clone(), ctx, command).await,
+            "whosaid-stop" => commands::stop::run(self.games.clone(), ctx, command).await,
+            "emoji" => commands::emoji::run(database, ctx, command).await,
+            "whosaid-filters" => commands::filters::run(database, ctx, command).await,
+            "whosaid-config" => {
+                commands::config::run(database, self.scrapper.clone(), ctx, command).await
             }
+            "leaderboard" => commands::leaderboard::run(database, ctx, command).await,
+            "whosaid-optout" => commands::optout::run(database, ctx, command).await,
+            "whosaid-optin" => commands::optin::run(database, ctx, command).await,
+            "whosaid-forget-me" => commands::forget_me::run(database, ctx, command).await,
+            _ => Err(CommandError::UnknownCommand),
+        };
+
+        if let Err(error) = result {
+            error.report(ctx, command).await;
         }
     }
 }
@@ -118,7 +222,9 @@ async fn main() -> Result<()> {
     // Configure the client with your Discord bot token in the environment.
     let token = env::var("WHOSAID_DISCORD_TOKEN").expect("Expected WHOSAID_DISCORD_TOKEN in the environment");
     // Set gateway intents, which decides what events the bot will be notified about
-    let intents = GatewayIntents::GUILD_MESSAGES
+    let intents = GatewayIntents::GUILDS
+        | GatewayIntents::GUILD_MEMBERS
+        | GatewayIntents::GUILD_MESSAGES
         | GatewayIntents::DIRECT_MESSAGES
         | GatewayIntents::MESSAGE_CONTENT;
 
@@ -133,8 +239,16 @@ async fn main() -> Result<()> {
     //
     // Shards will automatically attempt to reconnect, and will perform exponential backoff until
     // it reconnects.
-    if let Err(why) = client.start().await {
-        println!("Client error: {why:?}");
+    match client.start().await {
+        Ok(()) => {}
+        // Discord closes the connection with the code 4014 when an intent is not enabled.
+        Err(SerenityError::Gateway(GatewayError::DisallowedGatewayIntents)) => {
+            error!(
+                "Discord refused the privileged intents, enable the Server Members Intent and the \
+                Message Content Intent of the bot in the developer portal"
+            );
+        }
+        Err(why) => println!("Client error: {why:?}"),
     }
 
     Ok(())
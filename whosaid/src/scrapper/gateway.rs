@@ -0,0 +1,79 @@
+use serenity::all::{
+    ChannelId, Context, GuildChannel, GuildId, Member, Message, MessageId, MessageUpdateEvent, User,
+};
+
+use crate::scrapper::Scrapper;
+
+impl Scrapper {
+    pub async fn save_live_message(&self, message: &Message) -> anyhow::Result<()> {
+        let Some(guild_id) = message.guild_id else {
+            return Ok(());
+        };
+
+        // Messages from channels not caught up yet are fetched by the running scrap.
+        if !self.is_synced(message.channel_id) {
+            return Ok(());
+        }
+
+        self.database
+            .save_messages(guild_id, std::slice::from_ref(message))
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn update_live_message(&self, event: &MessageUpdateEvent) -> anyhow::Result<()> {
+        if let (Some(content), Some(edited_timestamp)) = (&event.content, event.edited_timestamp) {
+            self.database
+                .update_message(event.id, content, edited_timestamp.fixed_offset())
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn delete_live_messages(&self, message_ids: &[MessageId]) -> anyhow::Result<()> {
+        self.database.set_messages_deleted(message_ids).await?;
+
+        Ok(())
+    }
+
+    /// Channels and threads created during the session are only listed by the next scrap, scrap
+    /// them right away so their messages are saved live.
+    pub async fn save_live_channel(
+        &self,
+        ctx: &Context,
+        channel: &GuildChannel,
+    ) -> anyhow::Result<()> {
+        if !channel.is_text_based() {
+            return Ok(());
+        }
+
+        self.database
+            .save_channels(std::slice::from_ref(channel))
+            .await?;
+        self.scrap_channel(ctx, channel).await
+    }
+
+    pub async fn save_live_member(&self, member: &Member) -> anyhow::Result<()> {
+        self.database.save_user(&member.user).await?;
+        self.database
+            .save_guild_members(member.guild_id, std::slice::from_ref(member))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Members who leave keep their messages, they are only flagged as former members.
+    pub async fn remove_live_member(&self, guild_id: GuildId, user: &User) -> anyhow::Result<()> {
+        self.database
+            .set_guild_member_left(guild_id, user.id)
+            .await?;
+
+        Ok(())
+    }
+
+    pub(super) fn is_synced(&self, channel_id: ChannelId) -> bool {
+        self.synced_channels.lock().unwrap().contains(&channel_id)
+    }
+}
@@ -1,16 +1,37 @@
-use std::collections::HashSet;
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use serenity::all::{Context, GetMessages, GuildChannel, MessageId, User};
+use itertools::Itertools;
+use serenity::all::{
+    ChannelId, ChannelType, Context, GetMessages, GuildChannel, GuildId, LightMethod, Member,
+    MessageId, Request, Route, ThreadsData, Timestamp,
+};
+use serenity::constants::MEMBER_FETCH_LIMIT;
 use tokio::sync::Semaphore;
+use tokio::time::{Instant, MissedTickBehavior};
 use tracing::{error, info};
 
 use crate::database::Database;
 
+mod gateway;
+
+/// Default time between two catch-ups of the channels of a guild.
+pub const SCRAPE_INTERVAL: Duration = Duration::from_secs(60);
+pub const SCRAPE_INTERVAL_SECS_RANGE: RangeInclusive<u32> = 60..=86400;
+/// Messages saved before the last one checked again on each catch-up, a page of messages.
+const RECONCILED_MESSAGES: u8 = 100;
+
 #[derive(Clone)]
 pub struct Scrapper {
     database: Arc<Database>,
     scrap_semaphore: Arc<Semaphore>,
+    /// Channels caught up since the current gateway session started, for which gateway events
+    /// can be saved as they come without leaving a gap behind them.
+    synced_channels: Arc<Mutex<HashSet<ChannelId>>>,
+    gap_recovery_started: Arc<AtomicBool>,
 }
 
 impl Scrapper {
@@ -18,17 +39,24 @@ impl Scrapper {
         Self {
             database,
             scrap_semaphore: Arc::from(Semaphore::const_new(1)),
+            synced_channels: Arc::new(Mutex::new(HashSet::new())),
+            gap_recovery_started: Arc::new(AtomicBool::new(false)),
         }
     }
 }
 
 impl Scrapper {
+    /// Backfills every channel and fetches the messages sent while the bot was not listening to
+    /// the gateway. Runs after each new gateway session, live events keep the database current in
+    /// between.
     pub async fn scrap(&self, ctx: &Context) {
-        let Ok(permit) = self.scrap_semaphore.try_acquire() else {
-            info!("Scrapping already running, skipping");
+        let Ok(permit) = self.scrap_semaphore.acquire().await else {
             return;
         };
 
+        // A scrap of the previous session still running would mark its channels synced again.
+        self.synced_channels.lock().unwrap().clear();
+
         info!("Scrapping started");
 
         if let Err(e) = self._scrap(ctx).await {
@@ -40,6 +68,67 @@ impl Scrapper {
         drop(permit);
     }
 
+    /// Catches up the synced channels of each guild once its scrape interval has elapsed. Gateway
+    /// events can be missed while the session goes on, as during a short disconnection, the
+    /// messages they carried are recovered this way. Runs once for the lifetime of the bot.
+    pub async fn recover_gaps(&self, ctx: &Context) {
+        if self.gap_recovery_started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let mut last_recoveries: HashMap<GuildId, Instant> = HashMap::new();
+        // Guilds are checked as often as the shortest interval allows.
+        let mut interval = tokio::time::interval(Duration::from_secs(
+            (*SCRAPE_INTERVAL_SECS_RANGE.start()).into(),
+        ));
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            // A running scrap catches up every channel anyway.
+            let Ok(permit) = self.scrap_semaphore.try_acquire() else {
+                continue;
+            };
+
+            if let Err(e) = self.recover_due_gaps(ctx, &mut last_recoveries).await {
+                error!("Recovering missed messages failed: {}", e);
+            }
+
+            drop(permit);
+        }
+    }
+
+    async fn recover_due_gaps(
+        &self,
+        ctx: &Context,
+        last_recoveries: &mut HashMap<GuildId, Instant>,
+    ) -> anyhow::Result<()> {
+        for guild in self.database.get_guilds().await? {
+            let guild_id = GuildId::new(guild.id as u64);
+            let config = self.database.get_guild_config(guild_id).await?;
+
+            // Guilds are caught up by the scrap first, their interval starts then.
+            let last_recovery = last_recoveries.entry(guild_id).or_insert_with(Instant::now);
+            if last_recovery.elapsed() < config.scrape_interval {
+                continue;
+            }
+            *last_recovery = Instant::now();
+
+            for channel_id in self.database.get_guild_channel_ids(guild_id).await? {
+                if !self.is_synced(channel_id) {
+                    continue;
+                }
+
+                if let Err(e) = self.catch_up(ctx, guild_id, channel_id).await {
+                    error!("Catching up channel {} failed: {}", channel_id, e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     async fn _scrap(&self, ctx: &Context) -> anyhow::Result<()> {
         if let Ok(guilds) = self.database.get_guilds().await {
             info!("Guilds: {:?}", guilds);
@@ -53,25 +142,38 @@ impl Scrapper {
 
             self.database.save_user(&guild_owner).await?;
 
-            let guild_members = ctx.http.get_guild_members(guild.id, None, None).await?;
+            let guild_members = self.get_guild_members(ctx, guild.id).await?;
             let guild_users: Vec<_> = guild_members.iter().map(|member| &member.user).collect();
 
             self.database.save_users(&guild_users).await?;
             self.database.save_guild(&partial_guild).await?;
+            self.database
+                .save_guild_members(guild.id, &guild_members)
+                .await?;
+            self.database
+                .set_former_guild_members(guild.id, &guild_members)
+                .await?;
 
-            let channels: Vec<GuildChannel> = ctx
-                .http
-                .get_channels(guild.id)
-                .await?
-                .into_iter()
+            let guild_channels = ctx.http.get_channels(guild.id).await?;
+
+            // Forum channels have no messages of their own, only their posts are scrapped.
+            let forums: Vec<GuildChannel> = guild_channels
+                .iter()
+                .filter(|channel| channel.kind == ChannelType::Forum)
+                .cloned()
+                .collect();
+            let mut channels: Vec<GuildChannel> = guild_channels
+                .iter()
                 .filter(|channel| channel.is_text_based())
+                .cloned()
                 .collect();
-            self.database.save_channels(&channels).await?;
+            channels.extend(self.get_threads(ctx, guild.id, &guild_channels).await?);
 
-            let guild_users: HashSet<_> = guild_users.into_iter().collect();
+            self.database.save_channels(&forums).await?;
+            self.database.save_channels(&channels).await?;
 
             for channel in channels {
-                match self.scrap_channel(ctx, &channel, &guild_users).await {
+                match self.scrap_channel(ctx, &channel).await {
                     Ok(_) => {
                         info!(
                             "Scrapped channel {} from guild {}",
@@ -91,57 +193,258 @@ impl Scrapper {
         Ok(())
     }
 
-    async fn scrap_channel(
+    async fn get_guild_members(
+        &self,
+        ctx: &Context,
+        guild_id: GuildId,
+    ) -> anyhow::Result<Vec<Member>> {
+        let mut members: Vec<Member> = vec![];
+
+        loop {
+            let after = members.last().map(|member| member.user.id.get());
+            let page = ctx
+                .http
+                .get_guild_members(guild_id, Some(MEMBER_FETCH_LIMIT), after)
+                .await?;
+            let is_last_page = (page.len() as u64) < MEMBER_FETCH_LIMIT;
+
+            members.extend(page);
+
+            if is_last_page {
+                return Ok(members);
+            }
+        }
+    }
+
+    /// Lists the active threads of a guild and the archived public threads of its channels,
+    /// including forum posts.
+    async fn get_threads(
+        &self,
+        ctx: &Context,
+        guild_id: GuildId,
+        guild_channels: &[GuildChannel],
+    ) -> anyhow::Result<Vec<GuildChannel>> {
+        let mut threads = ctx.http.get_guild_active_threads(guild_id).await?.threads;
+
+        let thread_parents = guild_channels.iter().filter(|channel| {
+            matches!(
+                channel.kind,
+                ChannelType::Text | ChannelType::News | ChannelType::Forum
+            )
+        });
+
+        for channel in thread_parents {
+            match self.get_archived_threads(ctx, channel.id).await {
+                Ok(archived_threads) => threads.extend(archived_threads),
+                Err(e) => {
+                    error!(
+                        "Listing archived threads of channel {} failed: {}",
+                        channel.name, e
+                    );
+                }
+            }
+        }
+
+        Ok(threads.into_iter().unique_by(|thread| thread.id).collect())
+    }
+
+    async fn get_archived_threads(
         &self,
         ctx: &Context,
-        channel: &GuildChannel,
-        guild_users: &HashSet<&User>,
+        channel_id: ChannelId,
+    ) -> anyhow::Result<Vec<GuildChannel>> {
+        let mut threads: Vec<GuildChannel> = vec![];
+        let mut before: Option<Timestamp> = None;
+
+        loop {
+            // `Http::get_channel_archived_public_threads` takes an id as cursor, while Discord
+            // expects the archive timestamp of the last thread.
+            let mut params = vec![("limit", "100".to_string())];
+            if let Some(before) = before {
+                params.push(("before", before.to_string()));
+            }
+
+            let request = Request::new(
+                Route::ChannelArchivedPublicThreads { channel_id },
+                LightMethod::Get,
+            )
+            .params(Some(params));
+
+            let page: ThreadsData = ctx.http.fire(request).await?;
+
+            before = page
+                .threads
+                .iter()
+                .filter_map(|thread| thread.thread_metadata?.archive_timestamp)
+                .min();
+            threads.extend(page.threads);
+
+            if !page.has_more || before.is_none() {
+                return Ok(threads);
+            }
+        }
+    }
+
+    /// Applies a change of the scrape rules of a channel to it and its saved threads: the ones
+    /// not scraped anymore are purged, the others are scraped right away.
+    pub async fn refresh_channel(
+        &self,
+        ctx: &Context,
+        channel_id: ChannelId,
     ) -> anyhow::Result<()> {
+        let Some(channel) = channel_id.to_channel(ctx).await?.guild() else {
+            return Ok(());
+        };
+        self.database
+            .save_channels(std::slice::from_ref(&channel))
+            .await?;
+
+        let mut channel_ids = vec![channel_id];
+        channel_ids.extend(self.database.get_channel_threads(channel_id).await?);
+
+        for channel_id in channel_ids {
+            if !self.database.is_channel_scraped(channel_id).await? {
+                self.forget_channel(channel_id).await?;
+                continue;
+            }
+
+            if !self.is_synced(channel_id)
+                && let Some(channel) = channel_id.to_channel(ctx).await?.guild()
+            {
+                self.scrap_channel(ctx, &channel).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Stops saving the messages of a channel and removes the saved ones.
+    async fn forget_channel(&self, channel_id: ChannelId) -> anyhow::Result<()> {
+        self.synced_channels.lock().unwrap().remove(&channel_id);
+        self.database.purge_channel(channel_id).await?;
+
+        Ok(())
+    }
+
+    async fn scrap_channel(&self, ctx: &Context, channel: &GuildChannel) -> anyhow::Result<()> {
+        // Channels left out after being scrapped, as NSFW ones by default, are purged.
+        if !self.database.is_channel_scraped(channel.id).await? {
+            return self.forget_channel(channel.id).await;
+        }
+
         let db_channel = self.database.get_channel(channel.id).await?;
 
-        let mut backfill_done = db_channel.backfill_done;
+        if !db_channel.backfill_done {
+            // Opted out users' messages are not saved, so the cursor can be older than the first
+            // saved message.
+            let mut cursor = self
+                .database
+                .get_channel_first_message(channel.id)
+                .await?
+                .map(|message| MessageId::new(message.id as u64));
+
+            loop {
+                let builder = match cursor {
+                    None => GetMessages::new(),
+                    Some(cursor) => GetMessages::new().before(cursor),
+                };
 
-        while !backfill_done {
-            let db_channel_first_message =
-                self.database.get_channel_first_message(channel.id).await?;
+                let messages = channel.messages(&ctx, builder).await?;
 
-            let builder = match db_channel_first_message {
-                None => GetMessages::new(),
-                Some(db_channel_first_message) => {
-                    GetMessages::new().before(MessageId::new(db_channel_first_message.id as u64))
+                match messages.iter().map(|message| message.id).min() {
+                    None => {
+                        self.database.set_channel_backfilled(channel.id).await?;
+                        break;
+                    }
+                    Some(first_message_id) => {
+                        self.database
+                            .save_messages(channel.guild_id, &messages)
+                            .await?;
+                        cursor = Some(first_message_id);
+                    }
                 }
-            };
+            }
+        }
 
-            let messages = channel.messages(&ctx, builder).await?;
+        // From now on gateway events are saved directly, the catch-up makes sure the messages sent
+        // before them are still fetched.
+        self.synced_channels.lock().unwrap().insert(channel.id);
 
-            if messages.is_empty() {
-                backfill_done = true;
-                self.database.set_channel_backfilled(channel.id).await?;
-            } else {
-                self.database.save_messages(&messages, guild_users).await?;
-            }
+        self.catch_up(ctx, channel.guild_id, channel.id).await
+    }
+
+    /// Fetches the messages sent after the last saved message of a channel.
+    async fn catch_up(
+        &self,
+        ctx: &Context,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+    ) -> anyhow::Result<()> {
+        let mut cursor = self
+            .database
+            .get_channel_last_message(channel_id)
+            .await?
+            .map(|message| MessageId::new(message.id as u64));
+
+        if let Some(cursor) = cursor {
+            self.reconcile(ctx, guild_id, channel_id, cursor).await?;
         }
 
         loop {
-            let db_channel_last_message =
-                self.database.get_channel_last_message(channel.id).await?;
-
-            let builder = match db_channel_last_message {
+            let builder = match cursor {
                 None => GetMessages::new().limit(u8::MAX),
-                Some(db_channel_last_message) => GetMessages::new()
-                    .limit(u8::MAX)
-                    .after(MessageId::new(db_channel_last_message.id as u64)),
+                Some(cursor) => GetMessages::new().limit(u8::MAX).after(cursor),
             };
 
-            let messages = channel.messages(&ctx, builder).await?;
+            let messages = channel_id.messages(&ctx, builder).await?;
 
-            if messages.is_empty() {
-                break;
-            } else {
-                self.database.save_messages(&messages, guild_users).await?;
+            match messages.iter().map(|message| message.id).max() {
+                None => break,
+                Some(last_message_id) => {
+                    self.database.save_messages(guild_id, &messages).await?;
+                    cursor = Some(last_message_id);
+                }
             }
         }
 
         Ok(())
     }
+
+    /// Fetches again the last saved messages of a channel, up to `cursor`, as the edits and
+    /// deletions missed with the gateway events are not seen otherwise: the edits are saved, and
+    /// the messages Discord does not return anymore are marked deleted. Older messages are only
+    /// updated by live events.
+    async fn reconcile(
+        &self,
+        ctx: &Context,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        cursor: MessageId,
+    ) -> anyhow::Result<()> {
+        let builder = GetMessages::new()
+            .limit(RECONCILED_MESSAGES)
+            .before(MessageId::new(cursor.get() + 1));
+        let messages = channel_id.messages(&ctx, builder).await?;
+
+        // A page which is not full goes back to the start of the channel.
+        let oldest = match messages.len() < RECONCILED_MESSAGES as usize {
+            true => None,
+            false => messages.iter().map(|message| message.id).min(),
+        };
+        let fetched: HashSet<MessageId> = messages.iter().map(|message| message.id).collect();
+        let deleted: Vec<MessageId> = self
+            .database
+            .get_channel_message_ids(channel_id, oldest, cursor)
+            .await?
+            .into_iter()
+            .filter(|message_id| !fetched.contains(message_id))
+            .collect();
+
+        self.database.save_messages(guild_id, &messages).await?;
+        if !deleted.is_empty() {
+            self.database.set_messages_deleted(&deleted).await?;
+        }
+
+        Ok(())
+    }
 }